@@ -231,6 +231,69 @@ export const ABI = [
       }
     ]
   },
+  {
+    "type": "impl",
+    "name": "RecordIntegrity",
+    "interface_name": "contract::IRecordIntegrity"
+  },
+  {
+    "type": "interface",
+    "name": "contract::IRecordIntegrity",
+    "items": [
+      {
+        "type": "function",
+        "name": "anchor_record_hash",
+        "inputs": [
+          {
+            "name": "context_id",
+            "type": "core::felt252"
+          },
+          {
+            "name": "record_id",
+            "type": "core::felt252"
+          },
+          {
+            "name": "version",
+            "type": "core::integer::u32"
+          },
+          {
+            "name": "content_hash",
+            "type": "core::integer::u256"
+          }
+        ],
+        "outputs": [],
+        "state_mutability": "external"
+      },
+      {
+        "type": "function",
+        "name": "get_record_anchor",
+        "inputs": [
+          {
+            "name": "anchored_by",
+            "type": "core::starknet::contract_address::ContractAddress"
+          },
+          {
+            "name": "context_id",
+            "type": "core::felt252"
+          },
+          {
+            "name": "record_id",
+            "type": "core::felt252"
+          },
+          {
+            "name": "version",
+            "type": "core::integer::u32"
+          }
+        ],
+        "outputs": [
+          {
+            "type": "core::integer::u256"
+          }
+        ],
+        "state_mutability": "view"
+      }
+    ]
+  },
   {
     "type": "impl",
     "name": "DataAccessLogging",
@@ -428,6 +491,38 @@ export const ABI = [
       }
     ]
   },
+  {
+    "type": "event",
+    "name": "contract::health_data::RecordHashAnchored",
+    "kind": "struct",
+    "members": [
+      {
+        "name": "context_id",
+        "type": "core::felt252",
+        "kind": "data"
+      },
+      {
+        "name": "record_id",
+        "type": "core::felt252",
+        "kind": "data"
+      },
+      {
+        "name": "version",
+        "type": "core::integer::u32",
+        "kind": "data"
+      },
+      {
+        "name": "content_hash",
+        "type": "core::integer::u256",
+        "kind": "data"
+      },
+      {
+        "name": "anchored_by",
+        "type": "core::starknet::contract_address::ContractAddress",
+        "kind": "data"
+      }
+    ]
+  },
   {
     "type": "event",
     "name": "contract::health_data::Event",
@@ -462,6 +557,11 @@ export const ABI = [
         "name": "ParticipantAdded",
         "type": "contract::health_data::ParticipantAdded",
         "kind": "nested"
+      },
+      {
+        "name": "RecordHashAnchored",
+        "type": "contract::health_data::RecordHashAnchored",
+        "kind": "nested"
       }
     ]
   }
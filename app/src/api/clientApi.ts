@@ -15,7 +15,10 @@ export interface GrantConsentRequest {
 }
 
 export enum ClientMethod {
-  GET_PATIENT_RECORDS = 'get_patient_data',
+  REGISTER_IDENTITY = 'register_identity',
+  WHOAMI = 'whoami',
+  GET_MY_ROLES = 'get_my_roles',
+  GET_PATIENT_RECORDS = 'get_patient_records',
   STORE_PATIENT_DATA = 'store_patient_data',
   ADD_CONSENT = 'add_consent',
   ACCESS_PATIENT_DATA = 'access_patient_data',
@@ -23,15 +26,35 @@ export enum ClientMethod {
   CREATE_RESEARCH_POOL = 'create_research_pool',
   GET_RESEARCH_POOL = 'get_research_pool',
   REVOKE_ACCESS = 'revoke_access',
+  REVOKE_CONSENT = 'revoke_consent',
+  GRANT_ACCESS = 'grant_access',
   DELETE_PATIENT_DATA = 'delete_patient_data',
   UPDATE_RESEARCH_POOL = 'update_research_pool',
   DELETE_RESEARCH_POOL = 'delete_research_pool',
   UPDATE_PATIENT_DATA = 'update_patient_data',
   LIST_RESEARCH_POOLS = 'list_research_pools',
+  LIST_ENTITY_POOLS = 'list_entity_pools',
   UPDATE_SUBMISSION_STATUS = 'update_submission_status',
   GET_POOL_SUBMISSIONS    = 'get_pool_submissions',
+  GET_POOL_ANONYMIZED_DATA = 'get_pool_anonymized_data',
   SUBMIT_TO_POOL = 'submit_to_pool',
+  WITHDRAW_SUBMISSION = 'withdraw_submission',
   GET_PATIENT_SUBMISSIONS = 'get_patient_submissions',
+  FUND_POOL = 'fund_pool',
+  GET_POOL_LEDGER = 'get_pool_ledger',
+  CLAIM_REWARD = 'claim_reward',
+  GET_REWARD_BALANCE = 'get_reward_balance',
+  IMPORT_FHIR = 'import_fhir',
+  EXPORT_FHIR_BUNDLE = 'export_fhir_bundle',
+  GET_RECORD_HISTORY = 'get_record_history',
+  GET_RECORD_VERSION = 'get_record_version',
+  LIST_DELETED_RECORDS = 'list_deleted_records',
+  RESTORE_RECORD = 'restore_record',
+  FORGET_PATIENT = 'forget_patient',
+  EXPORT_PATIENT_BUNDLE = 'export_patient_bundle',
+  IMPORT_PATIENT_BUNDLE = 'import_patient_bundle',
+  VERIFY_RECORD_INTEGRITY = 'verify_record_integrity',
+  ANCHOR_RECORD_HASH = 'anchor_record_hash',
 
 }
 
@@ -1,38 +1,101 @@
 import {
-  ApiResponse,
   JsonRpcClient,
   RequestConfig,
   RpcError,
 } from '@calimero-network/calimero-client';
-import { PatientRecord } from '../types/HealthTypes';
+import { HealthError, HealthResponse, PatientRecord } from '../types/HealthTypes';
+import { toHealthError } from '../utils/healthError';
 import { getJWTObject } from '../utils/storage';
 import { createJwtHeader } from '../utils/jwtHeaders';
 import { getContextId } from '../utils/node';
 import { ClientMethod } from './clientApi';
 import { getNodeUrl } from '../utils/node';
-import { getUserID  } from '../utils/UserIdGenerate';
+import { clearUserID, getUserID, setUserID } from '../utils/UserIdGenerate';
 interface StorePatientDataArgs {
   patient_id: string;
   encrypted_data: number[];
   record_type: string;
+  structured?: ClinicalEntry[];
 }
+
+export interface Coding {
+  system: string;
+  code: string;
+  display?: string;
+}
+
+export type ObservationValue =
+  | { type: 'quantity'; value: { value: number; unit: string } }
+  | { type: 'coded'; value: Coding }
+  | { type: 'text'; value: string }
+  | { type: 'boolean'; value: boolean };
+
+// Optional coded entries stored next to the encrypted payload. Dates are ISO 8601.
+export type ClinicalEntry =
+  | { kind: 'observation'; code: Coding; value?: ObservationValue; effective?: string }
+  | { kind: 'condition'; code: Coding; clinical_status?: string; onset?: string; abatement?: string }
+  | { kind: 'medication'; code: Coding; dosage?: string; start?: string; end?: string }
+  | { kind: 'allergy'; substance: Coding; reaction?: Coding; severity?: 'mild' | 'moderate' | 'severe' }
+  | { kind: 'encounter'; class: Coding; reason?: Coding; start: string; end?: string };
 interface AddConsentArgs {
   patient_id: string;
   entity_id: string;
   purpose: string;
+  duration_secs: number;
   starknet_proof: number[]; // byte array for proof
 }
+
+const DEFAULT_CONSENT_DURATION_SECS = 90 * 24 * 60 * 60; // 90 days
 interface AccessRequestArgs {
   patient_id: string;
 }
 
-interface ResearchPool {
+type DenialReason =
+  | 'not_found'
+  | 'consent_missing'
+  | 'consent_expired'
+  | 'grant_expired'
+  | 'not_authorized'
+  | 'not_anonymized'
+  | 'anonymized_only'; // Shared through a research pool, so only the anonymized data is readable
+
+// Read methods return either the data or the reason access was refused
+type AccessResult<T> =
+  | { status: 'granted'; data: T }
+  | { status: 'denied'; data: DenialReason };
+
+// A participant's de-identified data as the pool owner receives it; the
+// subject is the participant ID and dates are shifted per pool
+export interface AnonymizedRecord {
+  anon_id: string;
+  subject_pseudonym: string;
+  record_type: string;
+  data: number[];
+  method: 'derived' | 'registered';
+  proof: number[] | null;
+  created_at: number;
+}
+
+type PoolStatus = 'draft' | 'active' | 'paused' | 'closed' | 'cancelled';
+
+type SubmissionStatus = 'pending' | 'accepted' | 'rejected' | 'withdrawn' | 'rewarded';
+
+interface EligibilityCriteria {
+  required_record_types?: string[];
+  max_record_age_secs?: number | null;
+  require_anonymized?: boolean;
+}
+
+export interface ResearchPool {
+  pool_id: string;
   title: string;
   description: string;
   reward_amount: number;
   entity_id: string;
   created_at: number;
-  status: string;
+  status: PoolStatus;
+  max_participants: number | null;
+  eligibility: EligibilityCriteria;
 }
 
 interface CreateResearchPoolArgs {
@@ -44,20 +107,179 @@ interface CreateResearchPoolArgs {
 
 // Add interface for update args
 interface UpdateResearchPoolArgs {
-  entity_id: string;
+  pool_id: string;
   title?: string; 
   description?: string;
   reward_amount?: number;
-  status?: string;
+  status?: PoolStatus;
+  max_participants?: number;
+  eligibility?: EligibilityCriteria;
+}
+
+interface PoolLedger {
+  pool_id: string;
+  funded: number;
+  reserved: number;
+  paid: number;
+  accepted_count: number;
+  deposit_refs: string[];
+}
+
+interface RewardEntry {
+  pool_id: string;
+  patient_id: string;
+  amount: number;
+  status: 'reserved' | 'paid';
+  reserved_at: number;
+  paid_at: number | null;
+  settlement_ref: string | null;
+}
+
+interface RewardBalance {
+  patient_id: string;
+  claimable: number;
+  paid: number;
+  rewards: RewardEntry[];
+}
+
+interface PatientDataResponse {
+  record_id: string;
+  data: number[];
+  record_type: string;
+  timestamp: number;
+  owner_id: string;
+  structured: ClinicalEntry[];
+  version: number;
+  content_hash: string; // Hex SHA-256 of `data`
+}
+
+export interface IntegrityAnchor {
+  version: number;
+  content_hash: string;
+  tx_hash: string; // Starknet transaction that anchored the hash
+  account: string; // Starknet account that sent it
+  anchored_by: string;
+  anchored_at: number;
+}
+
+// Felt-encoded arguments of the contract's get_record_anchor, and the u256
+// it should return for the record as stored now
+export interface StarknetAnchorKey {
+  context_id: string;
+  record_id: string;
+  version: number;
+  content_hash: string;
+}
+
+export interface IntegrityReport {
+  record_id: string;
+  version: number;
+  stored_hash: string;
+  computed_hash: string;
+  intact: boolean;
+  tampered_versions: number[];
+  anchor: IntegrityAnchor | null;
+  chain_key: StarknetAnchorKey;
+}
+
+// Checksummed export of everything stored about a patient. Treat as opaque
+// and pass back unchanged to importPatientBundle.
+export interface PatientBundle {
+  format: string;
+  format_version: number;
+  patient_id: string;
+  source_context: string;
+  exported_at: number;
+  checksum: string;
+  [section: string]: unknown;
+}
+
+export interface BundleImportSummary {
+  record_ids: string[];
+  record_versions: number;
+  skipped_deleted: number;
+}
+
+export interface ErasureReceipt {
+  receipt_id: string;
+  subject_tombstone: string;
+  requested_by: string;
+  erased_at: number;
+  records: number;
+  record_versions: number;
+  anonymized_records: number;
+  consents: number;
+  pool_submissions: number;
+  rewards_released: number;
+  rewards_tombstoned: number;
+  access_log_entries: number;
+  identity_keys: number;
+}
+
+export interface DeletedRecord {
+  record_id: string;
+  record_type: string;
+  deleted_at: number;
+  purge_after: number; // Restorable until then
+  on_legal_hold: boolean;
+}
+
+// Provenance of a version imported from a patient bundle; the author is
+// only what the bundle claimed, the importer is recorded as author_id
+export interface ImportedFrom {
+  source_context: string;
+  claimed_author_id: string;
+}
+
+export interface RecordVersionInfo {
+  version: number;
+  record_type: string;
+  author_id: string;
+  reason: string | null;
+  created_at: number;
+  imported_from?: ImportedFrom | null;
+}
+
+export interface RecordVersion extends RecordVersionInfo {
+  record_id: string;
+  data: number[];
+  structured: ClinicalEntry[];
+}
+
+interface Page<T> {
+  items: T[];
+  total: number;
+  offset: number;
+}
+
+interface SubmissionStatusChange {
+  status: SubmissionStatus;
+  changed_at: number;
+  changed_by: string;
+  reason: string | null;
+}
+
+export type IdentityKind = 'Patient' | 'Provider' | 'ResearchEntity';
+
+export type Role = 'Patient' | 'Provider' | 'Researcher' | 'Admin' | 'Auditor' | 'HonestBroker';
+
+export interface IdentityProfile {
+  identity_id: string;
+  kind: IdentityKind;
+  display_name: string;
+  keys: string[]; // Executor keys bound to the identity
+  created_at: number;
 }
 
 interface PoolSubmission {
+  pool_id: string;
   patient_id: string;
   entity_id: string;
   submitted_at: number;
-  status: string;
+  status: SubmissionStatus;
+  record_ids: string[];
+  history: SubmissionStatusChange[];
 }
-const userId = getUserID();
 export class HealthDataApi {
   private rpcClient: JsonRpcClient;
   private applicationId: string;
@@ -76,9 +298,8 @@ export class HealthDataApi {
     const headers = createJwtHeader();
 
     if (!headers || !jwtObject || !jwtObject.executor_public_key) {
-      return {
-        error: { message: 'Authentication failed', code: 401 },
-      };
+      const error: HealthError = { code: 'UNAUTHENTICATED', message: 'Authentication failed' };
+      return { error };
     }
 
     return {
@@ -90,7 +311,82 @@ export class HealthDataApi {
     };
   }
 
-  async getPatientRecords(): Promise<ApiResponse<PatientRecord[]>> {
+  // Identity the executor key is registered as, or null. Caches the ID so
+  // every other call can act as it.
+  async whoami(): Promise<HealthResponse<IdentityProfile | null>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.WHOAMI,
+      argsJson: {},
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.query(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      const profile: IdentityProfile | null = response?.result?.output ?? null;
+      if (profile) setUserID(profile.identity_id);
+      return { data: profile, error: null };
+    } catch (err) {
+      console.error('Failed to look up identity:', err);
+      return { error: toHealthError(err, 'Failed to look up identity') };
+    }
+  }
+
+  // Binds the executor key to a new identity. Patients get the patient role
+  // straight away; provider and researcher roles are assigned by an admin.
+  async registerIdentity(
+    identityId: string,
+    kind: IdentityKind,
+    displayName: string
+  ): Promise<HealthResponse<IdentityProfile>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.REGISTER_IDENTITY,
+      argsJson: { identity_id: identityId, kind, display_name: displayName },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.execute(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      const profile: IdentityProfile = response?.result?.output;
+      setUserID(profile.identity_id);
+      return { data: profile, error: null };
+    } catch (err) {
+      console.error('Failed to register identity:', err);
+      return { error: toHealthError(err, 'Failed to register identity') };
+    }
+  }
+
+  async getMyRoles(): Promise<HealthResponse<Role[]>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.GET_MY_ROLES,
+      argsJson: {},
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.query(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: response?.result?.output ?? [], error: null };
+    } catch (err) {
+      console.error('Failed to get roles:', err);
+      return { error: toHealthError(err, 'Failed to get roles') };
+    }
+  }
+
+  async getPatientRecords(): Promise<HealthResponse<PatientRecord[]>> {
     const { jwtObject, config, error } = this.getConfigAndJwt();
     if (error) return { error };
 
@@ -99,8 +395,7 @@ export class HealthDataApi {
         contextId: jwtObject?.context_id ?? getContextId(),
         method: ClientMethod.GET_PATIENT_RECORDS,
         argsJson: {
-          patient_id: userId,
-          entity_id: userId,
+          patient_id: getUserID(),
         },
         executorPublicKey: jwtObject.executor_public_key,
       },
@@ -108,26 +403,24 @@ export class HealthDataApi {
     );
 
     if (response?.error) {
-      return { error: response.error };
+      return { error: toHealthError(response.error) };
     }
 
-    const recordData = response?.result?.output as { data: number[], timestamp: number, record_type: string } | undefined;
-    if (!recordData) {
-      return { data: [], error: null };
-    }
+    const results = (response?.result?.output ?? []) as PatientDataResponse[];
 
     const textDecoder = new TextDecoder();
-    const record: PatientRecord = {
+    const records: PatientRecord[] = results.map((recordData) => ({
+      record_id: recordData.record_id,
       data: textDecoder.decode(new Uint8Array(recordData.data)),
       timestamp: Number(recordData.timestamp) / 1000000, // Convert nanoseconds to milliseconds
       record_type: recordData.record_type,
-      owner_id: jwtObject.executor_public_key,
+      owner_id: recordData.owner_id,
       authorized_ids: [],
       is_anonymized: false,
-    };
-    console.log('data', record);
+    }));
+    console.log('data', records);
     return {
-      data: [record],
+      data: records,
 
       error: null,
     };
@@ -136,7 +429,9 @@ export class HealthDataApi {
   async storePatientData(
     encryptedData: Uint8Array,
     recordType: string,
-  ): Promise<ApiResponse<boolean>> {
+    structured?: ClinicalEntry[],
+    dataHash?: string,
+  ): Promise<HealthResponse<boolean>> {
     const { jwtObject, config, error } = this.getConfigAndJwt();
     if (error) {
       console.error('Authentication Error:', error);
@@ -152,9 +447,11 @@ export class HealthDataApi {
       contextId: jwtObject?.context_id ?? getContextId(),
       method: ClientMethod.STORE_PATIENT_DATA,
       argsJson: {
-        patient_id: userId,
+        patient_id: getUserID(),
         encrypted_data: Array.from(encryptedData),
+        data_hash: dataHash ?? null,
         record_type: recordType,
+        structured: structured ?? null,
       },
       executorPublicKey: jwtObject.executor_public_key,
     };
@@ -165,7 +462,7 @@ export class HealthDataApi {
 
     if (response?.error) {
       console.error('API Error:', response.error);
-      return { error: response.error };
+      return { error: toHealthError(response.error) };
     }
 
     console.log('=== Store Patient Data Response ===');
@@ -178,7 +475,8 @@ export class HealthDataApi {
     entityId: string,
     purpose?: string,
     starknetProof?: string,
-  ): Promise<ApiResponse<boolean>> {
+    durationSecs: number = DEFAULT_CONSENT_DURATION_SECS,
+  ): Promise<HealthResponse<boolean>> {
     const { jwtObject, config, error } = this.getConfigAndJwt();
     if (error) {
       console.error('Authentication Error:', error);
@@ -197,9 +495,10 @@ export class HealthDataApi {
       contextId: jwtObject?.context_id ?? getContextId(),
       method: ClientMethod.ADD_CONSENT,
       argsJson: {
-        patient_id: userId,
+        patient_id: getUserID(),
         entity_id: entityId,
         purpose: finalPurpose,
+        duration_secs: durationSecs,
         starknet_proof: finalProof,
       },
       executorPublicKey: jwtObject.executor_public_key,
@@ -212,7 +511,7 @@ export class HealthDataApi {
 
       if (response?.error) {
         console.error('API Error:', response.error);
-        return { error: response.error };
+        return { error: toHealthError(response.error) };
       }
 
       console.log('=== Add Consent Response ===');
@@ -222,19 +521,15 @@ export class HealthDataApi {
       return { data: true, error: null };
     } catch (err) {
       console.error('Failed to add consent:', err);
-      return {
-        error: {
-          message: 'Failed to add consent',
-          code: 500,
-        },
-      };
+      return { error: toHealthError(err, 'Failed to add consent') };
     }
   }
 
   async accessPatientData(
     patientId: string,
     entityId: string,
-  ): Promise<ApiResponse<Uint8Array>> {
+    purpose: string = 'data_access',
+  ): Promise<HealthResponse<Uint8Array>> {
     const { jwtObject, config, error } = this.getConfigAndJwt();
     if (error) {
       console.error('Authentication Error:', error);
@@ -252,6 +547,7 @@ export class HealthDataApi {
       argsJson: {
         patient_id: patientId,
         entity_id: entityId,
+        purpose,
       },
       executorPublicKey: jwtObject.executor_public_key,
     };
@@ -259,22 +555,23 @@ export class HealthDataApi {
     console.log('Request Parameters:', JSON.stringify(requestParams, null, 2));
 
     try {
-      const response = await this.rpcClient.query(requestParams, config);
+      const response = await this.rpcClient.execute(requestParams, config);
 
       if (response?.error) {
         console.error('API Error:', response.error);
-        return { error: response.error };
+        return { error: toHealthError(response.error) };
       }
 
-      const data = response?.result?.output;
-      if (!data) {
+      const result = response?.result?.output as AccessResult<number[]> | undefined;
+      if (!result || result.status !== 'granted') {
         return {
           error: {
-            message: 'No data found or access denied',
-            code: 404,
+            message: `Access denied: ${result?.data ?? 'not_found'}`,
+            code: result?.data === 'not_found' ? 404 : 403,
           },
         };
       }
+      const data = result.data;
 
       // Convert array data to Uint8Array
       const encryptedData = new Uint8Array(data);
@@ -287,16 +584,11 @@ export class HealthDataApi {
       return { data: encryptedData, error: null };
     } catch (err) {
       console.error('Failed to access patient data:', err);
-      return {
-        error: {
-          message: 'Failed to access patient data',
-          code: 500,
-        },
-      };
+      return { error: toHealthError(err, 'Failed to access patient data') };
     }
   }
 
-  async listAuthorizedReports(): Promise<ApiResponse<PatientRecord[]>> {
+  async listAuthorizedReports(): Promise<HealthResponse<PatientRecord[]>> {
     const { jwtObject, config, error } = this.getConfigAndJwt();
     if (error) {
       console.error('Authentication Error:', error);
@@ -311,23 +603,24 @@ export class HealthDataApi {
       contextId: jwtObject?.context_id ?? getContextId(),
       method: ClientMethod.LIST_AUTHORIZED_REPORTS,
       argsJson: {
-        entity_id: userId,
+        entity_id: getUserID(),
       },
       executorPublicKey: jwtObject.executor_public_key,
     };
 
     try {
-      const response = await this.rpcClient.query(requestParams, config);
+      const response = await this.rpcClient.execute(requestParams, config);
 
       if (response?.error) {
         console.error('API Error:', response.error);
-        return { error: response.error };
+        return { error: toHealthError(response.error) };
       }
 
       const records = response?.result?.output ?? [];
 
       // Convert backend records to frontend format
       const patientRecords: PatientRecord[] = records.map((record) => ({
+        record_id: record.record_id,
         data: new TextDecoder().decode(new Uint8Array(record.data)),
         timestamp: Number(record.timestamp) / 1000000, // Convert nanoseconds to milliseconds
         record_type: record.record_type,
@@ -344,12 +637,7 @@ export class HealthDataApi {
       return { data: patientRecords, error: null };
     } catch (err) {
       console.error('Failed to list authorized reports:', err);
-      return {
-        error: {
-          message: 'Failed to list authorized reports',
-          code: 500,
-        },
-      };
+      return { error: toHealthError(err, 'Failed to list authorized reports') };
     }
   }
 
@@ -358,7 +646,9 @@ export class HealthDataApi {
     description: string,
     rewardAmount: number,
     expiryDate: number,  
-): Promise<ApiResponse<boolean>> {
+    maxParticipants?: number,
+    eligibility?: EligibilityCriteria,
+): Promise<HealthResponse<string>> {
     const { jwtObject, config, error } = this.getConfigAndJwt();
     if (error) {
       console.error('Authentication Error:', error);
@@ -370,15 +660,15 @@ export class HealthDataApi {
     if (expiryDate <= currentTime) {
       return {
         error: {
+          code: 'INVALID_INPUT',
           message: 'Expiry date must be in the future',
-          code: 400,
         },
       };
     }
 
     console.log('=== Create Research Pool Request ===');
     console.log('Timestamp:', new Date().toISOString());
-    console.log('Entity ID:', userId);
+    console.log('Entity ID:', getUserID());
     console.log('Title:', title);
     console.log('Expiry Date:', new Date(expiryDate * 1000).toISOString());
 
@@ -386,11 +676,13 @@ export class HealthDataApi {
       contextId: jwtObject?.context_id ?? getContextId(),
       method: ClientMethod.CREATE_RESEARCH_POOL,
       argsJson: {
-        entity_id: userId,
+        entity_id: getUserID(),
         title,
         description,
         reward_amount: rewardAmount,
         expiry_date: expiryDate,  // Add expiry to args
+        max_participants: maxParticipants ?? null,
+        eligibility: eligibility ?? null,
       },
       executorPublicKey: jwtObject.executor_public_key,
     };
@@ -400,22 +692,17 @@ export class HealthDataApi {
 
       if (response?.error) {
         console.error('API Error:', response.error);
-        return { error: response.error };
+        return { error: toHealthError(response.error) };
       }
 
-      return { data: true, error: null };
+      return { data: response?.result?.output, error: null };
     } catch (err) {
       console.error('Failed to create research pool:', err);
-      return {
-        error: {
-          message: 'Failed to create research pool',
-          code: 500,
-        },
-      };
+      return { error: toHealthError(err, 'Failed to create research pool') };
     }
 }
 
-  async getResearchPool(): Promise<ApiResponse<ResearchPool>> {
+  async getResearchPool(poolId: string): Promise<HealthResponse<ResearchPool>> {
     const { jwtObject, config, error } = this.getConfigAndJwt();
     if (error) {
       console.error('Authentication Error:', error);
@@ -424,13 +711,13 @@ export class HealthDataApi {
 
     console.log('=== Get Research Pool Request ===');
     console.log('Timestamp:', new Date().toISOString());
-    // console.log('Entity ID:', entityId);
+    console.log('Pool ID:', poolId);
 
     const requestParams = {
       contextId: jwtObject?.context_id ?? getContextId(),
       method: ClientMethod.GET_RESEARCH_POOL,
       argsJson: {
-        entity_id: userId,
+        pool_id: poolId,
       },
       executorPublicKey: jwtObject.executor_public_key,
     };
@@ -440,15 +727,15 @@ export class HealthDataApi {
 
       if (response?.error) {
         console.error('API Error:', response.error);
-        return { error: response.error };
+        return { error: toHealthError(response.error) };
       }
 
       const pool = response?.result?.output;
       if (!pool) {
         return {
           error: {
+            code: 'NOT_FOUND',
             message: 'Research pool not found',
-            code: 404,
           },
         };
       }
@@ -456,16 +743,42 @@ export class HealthDataApi {
       return { data: pool, error: null };
     } catch (err) {
       console.error('Failed to get research pool:', err);
-      return {
-        error: {
-          message: 'Failed to get research pool',
-          code: 500,
-        },
-      };
+      return { error: toHealthError(err, 'Failed to get research pool') };
     }
   }
 
-  async deletePatientData(patientId: string): Promise<ApiResponse<boolean>> {
+  async listEntityPools(): Promise<HealthResponse<ResearchPool[]>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) {
+      console.error('Authentication Error:', error);
+      return { error };
+    }
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.LIST_ENTITY_POOLS,
+      argsJson: {
+        entity_id: getUserID(),
+      },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.query(requestParams, config);
+
+      if (response?.error) {
+        console.error('API Error:', response.error);
+        return { error: toHealthError(response.error) };
+      }
+
+      return { data: response?.result?.output ?? [], error: null };
+    } catch (err) {
+      console.error('Failed to list entity pools:', err);
+      return { error: toHealthError(err, 'Failed to list entity pools') };
+    }
+  }
+
+  async deletePatientData(patientId: string): Promise<HealthResponse<boolean>> {
     const { jwtObject, config, error } = this.getConfigAndJwt();
     if (error) return { error };
 
@@ -482,8 +795,10 @@ export class HealthDataApi {
   async updatePatientData(
     patientId: string, 
     newData: Uint8Array,
-    recordType: string
-  ): Promise<ApiResponse<boolean>> {
+    recordType: string,
+    structured?: ClinicalEntry[],
+    reason?: string
+  ): Promise<HealthResponse<boolean>> {
     const { jwtObject, config, error } = this.getConfigAndJwt();
     if (error) return { error };
 
@@ -493,7 +808,9 @@ export class HealthDataApi {
       argsJson: {
         patient_id: patientId,
         new_data: Array.from(newData),
-        record_type: recordType
+        record_type: recordType,
+        structured: structured ?? null,
+        reason: reason ?? null
       },
       executorPublicKey: jwtObject.executor_public_key,
     };
@@ -501,7 +818,7 @@ export class HealthDataApi {
     return await this.rpcClient.execute(requestParams, config);
   }
 
-  async deleteResearchPool(entityId: string): Promise<ApiResponse<boolean>> {
+  async deleteResearchPool(poolId: string): Promise<HealthResponse<boolean>> {
     const { jwtObject, config, error } = this.getConfigAndJwt();
     if (error) {
       console.error('Authentication Error:', error);
@@ -510,13 +827,13 @@ export class HealthDataApi {
 
     console.log('=== Delete Research Pool Request ===');
     console.log('Timestamp:', new Date().toISOString());
-    console.log('Entity ID:', entityId);
+    console.log('Pool ID:', poolId);
 
     const requestParams = {
       contextId: jwtObject?.context_id ?? getContextId(),
       method: ClientMethod.DELETE_RESEARCH_POOL,
       argsJson: {
-        entity_id: entityId
+        pool_id: poolId
       },
       executorPublicKey: jwtObject.executor_public_key,
     };
@@ -526,25 +843,20 @@ export class HealthDataApi {
 
       if (response?.error) {
         console.error('API Error:', response.error);
-        return { error: response.error };
+        return { error: toHealthError(response.error) };
       }
 
       return { data: true, error: null };
     } catch (err) {
       console.error('Failed to delete research pool:', err);
-      return {
-        error: {
-          message: 'Failed to delete research pool',
-          code: 500
-        }
-      };
+      return { error: toHealthError(err, 'Failed to delete research pool') };
     }
   }
 
   async updateResearchPool(
-    entityId: string,
+    poolId: string,
     updates: Partial<UpdateResearchPoolArgs>
-  ): Promise<ApiResponse<boolean>> {
+  ): Promise<HealthResponse<boolean>> {
     const { jwtObject, config, error } = this.getConfigAndJwt();
     if (error) {
       console.error('Authentication Error:', error);
@@ -553,15 +865,15 @@ export class HealthDataApi {
 
     console.log('=== Update Research Pool Request ===');
     console.log('Timestamp:', new Date().toISOString());
-    console.log('Entity ID:', entityId);
+    console.log('Pool ID:', poolId);
     console.log('Updates:', updates);
 
     const requestParams = {
       contextId: jwtObject?.context_id ?? getContextId(),
       method: ClientMethod.UPDATE_RESEARCH_POOL,
       argsJson: {
-        entity_id: entityId,
-        ...updates
+        ...updates,
+        pool_id: poolId,
       },
       executorPublicKey: jwtObject.executor_public_key,
     };
@@ -571,22 +883,17 @@ export class HealthDataApi {
 
       if (response?.error) {
         console.error('API Error:', response.error);
-        return { error: response.error };
+        return { error: toHealthError(response.error) };
       }
 
       return { data: true, error: null };
     } catch (err) {
       console.error('Failed to update research pool:', err);
-      return {
-        error: {
-          message: 'Failed to update research pool',
-          code: 500
-        }
-      };
+      return { error: toHealthError(err, 'Failed to update research pool') };
     }
   }
 
-  async revokeAccess(entityId: string): Promise<ApiResponse<boolean>> {
+  async revokeAccess(entityId: string): Promise<HealthResponse<boolean>> {
     const { jwtObject, config, error } = this.getConfigAndJwt();
     if (error) {
       console.error('Authentication Error:', error);
@@ -602,7 +909,7 @@ export class HealthDataApi {
       contextId: jwtObject?.context_id ?? getContextId(),
       method: ClientMethod.REVOKE_ACCESS,
       argsJson: {
-        patient_id: userId,
+        patient_id: getUserID(),
         entity_id: entityId
       },
       executorPublicKey: jwtObject.executor_public_key,
@@ -613,7 +920,7 @@ export class HealthDataApi {
 
       if (response?.error) {
         console.error('API Error:', response.error);
-        return { error: response.error };
+        return { error: toHealthError(response.error) };
       }
 
       console.log('=== Revoke Access Response ===');
@@ -623,16 +930,65 @@ export class HealthDataApi {
       return { data: true, error: null };
     } catch (err) {
       console.error('Failed to revoke access:', err);
-      return {
-        error: {
-          message: 'Failed to revoke access',
-          code: 500
-        }
-      };
+      return { error: toHealthError(err, 'Failed to revoke access') };
+    }
+  }
+
+  // Withdraws the consent for one purpose; grants and other purposes stay
+  async revokeConsent(entityId: string, purpose: string): Promise<HealthResponse<boolean>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.REVOKE_CONSENT,
+      argsJson: { patient_id: getUserID(), entity_id: entityId, purpose },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.execute(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: true, error: null };
+    } catch (err) {
+      console.error('Failed to revoke consent:', err);
+      return { error: toHealthError(err, 'Failed to revoke consent') };
+    }
+  }
+
+  // Lets the entity read the patient's records until `expiresAt` (Unix
+  // seconds); consents alone only cover reads made for their purpose
+  async grantAccess(
+    entityId: string,
+    expiresAt: number,
+    recordId?: string,
+  ): Promise<HealthResponse<boolean>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.GRANT_ACCESS,
+      argsJson: {
+        patient_id: getUserID(),
+        entity_id: entityId,
+        expires_at: expiresAt,
+        record_id: recordId ?? null,
+      },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.execute(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: true, error: null };
+    } catch (err) {
+      console.error('Failed to grant access:', err);
+      return { error: toHealthError(err, 'Failed to grant access') };
     }
   }
 
-  async listResearchPools(): Promise<ApiResponse<ResearchPool[]>> {
+  async listResearchPools(): Promise<HealthResponse<ResearchPool[]>> {
     const { jwtObject, config, error } = this.getConfigAndJwt();
     if (error) {
       console.error('Authentication Error:', error);
@@ -654,7 +1010,7 @@ export class HealthDataApi {
 
       if (response?.error) {
         console.error('API Error:', response.error);
-        return { error: response.error };
+        return { error: toHealthError(response.error) };
       }
 
       const pools = response?.result?.output ?? [];
@@ -670,16 +1026,14 @@ export class HealthDataApi {
       return { data: pools, error: null };
     } catch (err) {
       console.error('Failed to list research pools:', err);
-      return {
-        error: {
-          message: 'Failed to list research pools',
-          code: 500
-        }
-      };
+      return { error: toHealthError(err, 'Failed to list research pools') };
     }
   }
 
-  async submitToPool(entityId: string): Promise<ApiResponse<boolean>> {
+  async submitToPool(
+    poolId: string,
+    recordIds: string[] = [],
+  ): Promise<HealthResponse<boolean>> {
     const { jwtObject, config, error } = this.getConfigAndJwt();
     if (error) {
       console.error('Authentication Error:', error);
@@ -687,15 +1041,17 @@ export class HealthDataApi {
     }
 
     console.log('=== Submit To Pool Request ===');
-    console.log('Entity ID:', entityId);
+    console.log('Pool ID:', poolId);
+    console.log('Record IDs:', recordIds);
     console.log('Patient ID:', jwtObject.executor_public_key);
 
     const requestParams = {
       contextId: jwtObject?.context_id ?? getContextId(),
       method: ClientMethod.SUBMIT_TO_POOL,
       argsJson: {
-        entity_id: entityId,
-        patient_id: userId,
+        pool_id: poolId,
+        patient_id: getUserID(),
+        record_ids: recordIds,
       },
       executorPublicKey: jwtObject.executor_public_key,
     };
@@ -704,53 +1060,78 @@ export class HealthDataApi {
       const response = await this.rpcClient.execute(requestParams, config);
       if (response?.error) {
         console.error('API Error:', response.error);
-        return { error: response.error };
+        return { error: toHealthError(response.error) };
       }
       return { data: true, error: null };
     } catch (err) {
       console.error('Failed to submit to pool:', err);
-      return {
-        error: {
-          message: 'Failed to submit to pool',
-          code: 500
-        }
-      };
+      return { error: toHealthError(err, 'Failed to submit to pool') };
     }
   }
 
-  async getPoolSubmissions(entityId: string): Promise<ApiResponse<PoolSubmission[]>> {
+  async withdrawSubmission(
+    poolId: string,
+    reason?: string,
+  ): Promise<HealthResponse<boolean>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.WITHDRAW_SUBMISSION,
+      argsJson: {
+        pool_id: poolId,
+        patient_id: getUserID(),
+        reason: reason ?? null,
+      },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.execute(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: true, error: null };
+    } catch (err) {
+      console.error('Failed to withdraw submission:', err);
+      return { error: toHealthError(err, 'Failed to withdraw submission') };
+    }
+  }
+
+  async getPoolSubmissions(
+    poolId: string,
+    statuses?: SubmissionStatus[],
+    offset?: number,
+    limit?: number,
+  ): Promise<HealthResponse<Page<PoolSubmission>>> {
     const { jwtObject, config, error } = this.getConfigAndJwt();
     if (error) return { error };
 
     const requestParams = {
       contextId: jwtObject?.context_id ?? getContextId(),
       method: ClientMethod.GET_POOL_SUBMISSIONS,
-      argsJson: { entity_id: entityId },
+      argsJson: { pool_id: poolId, statuses, offset, limit },
       executorPublicKey: jwtObject.executor_public_key,
     };
 
     try {
       const response = await this.rpcClient.query(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
       return { 
-        data: response?.result?.output ?? [],
+        data: response?.result?.output ?? { items: [], total: 0, offset: 0 },
         error: null 
       };
     } catch (err) {
       console.error('Failed to get pool submissions:', err);
-      return {
-        error: {
-          message: 'Failed to get pool submissions',
-          code: 500
-        }
-      };
+      return { error: toHealthError(err, 'Failed to get pool submissions') };
     }
   }
 
   async updateSubmissionStatus(
-    entityId: string,
-    patientId: string,
-    status: string
-  ): Promise<ApiResponse<boolean>> {
+    poolId: string,
+    participantId: string,
+    status: SubmissionStatus,
+    reason?: string
+  ): Promise<HealthResponse<boolean>> {
     const { jwtObject, config, error } = this.getConfigAndJwt();
     if (error) return { error };
 
@@ -758,29 +1139,25 @@ export class HealthDataApi {
       contextId: jwtObject?.context_id ?? getContextId(),
       method: ClientMethod.UPDATE_SUBMISSION_STATUS,
       argsJson: {
-        entity_id: entityId,
-        patient_id: patientId,
-        status
+        pool_id: poolId,
+        participant_id: participantId,
+        status,
+        reason: reason ?? null
       },
       executorPublicKey: jwtObject.executor_public_key,
     };
 
     try {
       const response = await this.rpcClient.execute(requestParams, config);
-      if (response?.error) return { error: response.error };
+      if (response?.error) return { error: toHealthError(response.error) };
       return { data: true, error: null };
     } catch (err) {
       console.error('Failed to update submission status:', err);
-      return {
-        error: {
-          message: 'Failed to update submission status',
-          code: 500
-        }
-      };
+      return { error: toHealthError(err, 'Failed to update submission status') };
     }
   }
 
-  async getPatientSubmissions(): Promise<ApiResponse<PoolSubmission[]>> {
+  async getPatientSubmissions(): Promise<HealthResponse<PoolSubmission[]>> {
     const { jwtObject, config, error } = this.getConfigAndJwt();
     if (error) {
       console.error('Authentication Error:', error);
@@ -794,7 +1171,7 @@ export class HealthDataApi {
       contextId: jwtObject?.context_id ?? getContextId(),
       method: ClientMethod.GET_PATIENT_SUBMISSIONS,
       argsJson: {
-        patient_id: userId
+        patient_id: getUserID()
       },
       executorPublicKey: jwtObject.executor_public_key,
     };
@@ -803,7 +1180,7 @@ export class HealthDataApi {
       const response = await this.rpcClient.query(requestParams, config);
       if (response?.error) {
         console.error('API Error:', response.error);
-        return { error: response.error };
+        return { error: toHealthError(response.error) };
       }
 
       const submissions = response?.result?.output ?? [];
@@ -812,13 +1189,389 @@ export class HealthDataApi {
       return { data: submissions, error: null };
     } catch (err) {
       console.error('Failed to get patient submissions:', err);
-      return {
-        error: {
-          message: 'Failed to get patient submissions',
-          code: 500
-        }
-      };
+      return { error: toHealthError(err, 'Failed to get patient submissions') };
+    }
+  }
+
+  async fundPool(
+    poolId: string,
+    amount: number,
+    depositTxHash?: string,
+  ): Promise<HealthResponse<PoolLedger>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.FUND_POOL,
+      argsJson: {
+        pool_id: poolId,
+        amount,
+        deposit_ref: depositTxHash ?? null,
+      },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.execute(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: response?.result?.output, error: null };
+    } catch (err) {
+      console.error('Failed to fund pool:', err);
+      return { error: toHealthError(err, 'Failed to fund pool') };
+    }
+  }
+
+  async getPoolLedger(poolId: string): Promise<HealthResponse<PoolLedger>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.GET_POOL_LEDGER,
+      argsJson: { pool_id: poolId },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.query(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: response?.result?.output, error: null };
+    } catch (err) {
+      console.error('Failed to get pool ledger:', err);
+      return { error: toHealthError(err, 'Failed to get pool ledger') };
+    }
+  }
+
+  async claimReward(
+    poolId: string,
+    withdrawTxHash?: string,
+  ): Promise<HealthResponse<RewardEntry>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.CLAIM_REWARD,
+      argsJson: {
+        pool_id: poolId,
+        patient_id: getUserID(),
+        settlement_ref: withdrawTxHash ?? null,
+      },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.execute(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: response?.result?.output, error: null };
+    } catch (err) {
+      console.error('Failed to claim reward:', err);
+      return { error: toHealthError(err, 'Failed to claim reward') };
     }
   }
-}
 
+  async getRewardBalance(): Promise<HealthResponse<RewardBalance>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.GET_REWARD_BALANCE,
+      argsJson: { patient_id: getUserID() },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.query(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: response?.result?.output, error: null };
+    } catch (err) {
+      console.error('Failed to get reward balance:', err);
+      return { error: toHealthError(err, 'Failed to get reward balance') };
+    }
+  }
+
+  // Accepts a single FHIR R4 resource or a Bundle; returns the new record IDs
+  async importFhir(resource: object): Promise<HealthResponse<string[]>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.IMPORT_FHIR,
+      argsJson: { patient_id: getUserID(), resource },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.execute(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: response?.result?.output ?? [], error: null };
+    } catch (err) {
+      console.error('Failed to import FHIR data:', err);
+      return { error: toHealthError(err, 'Failed to import FHIR data') };
+    }
+  }
+
+  // FHIR Bundle of the patient's records the entity may see. Patients pass
+  // their own ID as the entity to export everything they hold.
+  async exportFhirBundle(patientId: string, entityId: string = getUserID()): Promise<HealthResponse<object>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.EXPORT_FHIR_BUNDLE,
+      argsJson: { patient_id: patientId, entity_id: entityId },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      // Exports are audited, so this is a mutating call
+      const response = await this.rpcClient.execute(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: response?.result?.output, error: null };
+    } catch (err) {
+      console.error('Failed to export FHIR bundle:', err);
+      return { error: toHealthError(err, 'Failed to export FHIR bundle') };
+    }
+  }
+
+  // Versions of a record, newest first, without their payloads
+  async getPoolAnonymizedData(
+    poolId: string,
+    participantId: string
+  ): Promise<HealthResponse<AccessResult<AnonymizedRecord[]>>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.GET_POOL_ANONYMIZED_DATA,
+      argsJson: { pool_id: poolId, participant_id: participantId },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      // Reads are audited, so this is a mutating call
+      const response = await this.rpcClient.execute(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: response?.result?.output, error: null };
+    } catch (err) {
+      console.error('Failed to get anonymized pool data:', err);
+      return { error: toHealthError(err, 'Failed to get anonymized pool data') };
+    }
+  }
+
+  async getRecordHistory(
+    recordId: string,
+    entityId: string = getUserID()
+  ): Promise<HealthResponse<AccessResult<RecordVersionInfo[]>>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.GET_RECORD_HISTORY,
+      argsJson: { record_id: recordId, entity_id: entityId },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      // Reads are audited, so this is a mutating call
+      const response = await this.rpcClient.execute(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: response?.result?.output, error: null };
+    } catch (err) {
+      console.error('Failed to get record history:', err);
+      return { error: toHealthError(err, 'Failed to get record history') };
+    }
+  }
+
+  async getRecordVersion(
+    recordId: string,
+    version: number,
+    entityId: string = getUserID()
+  ): Promise<HealthResponse<AccessResult<RecordVersion>>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.GET_RECORD_VERSION,
+      argsJson: { record_id: recordId, entity_id: entityId, version },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.execute(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: response?.result?.output, error: null };
+    } catch (err) {
+      console.error('Failed to get record version:', err);
+      return { error: toHealthError(err, 'Failed to get record version') };
+    }
+  }
+
+  // Soft-deleted records that can still be restored
+  async listDeletedRecords(): Promise<HealthResponse<DeletedRecord[]>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.LIST_DELETED_RECORDS,
+      argsJson: { patient_id: getUserID() },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.query(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: response?.result?.output ?? [], error: null };
+    } catch (err) {
+      console.error('Failed to list deleted records:', err);
+      return { error: toHealthError(err, 'Failed to list deleted records') };
+    }
+  }
+
+  async restoreRecord(recordId: string): Promise<HealthResponse<boolean>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.RESTORE_RECORD,
+      argsJson: { record_id: recordId },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.execute(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: true, error: null };
+    } catch (err) {
+      console.error('Failed to restore record:', err);
+      return { error: toHealthError(err, 'Failed to restore record') };
+    }
+  }
+
+  // Erases everything held about the patient. Cannot be undone.
+  async forgetPatient(patientId: string = getUserID()): Promise<HealthResponse<ErasureReceipt>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.FORGET_PATIENT,
+      argsJson: { patient_id: patientId },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.execute(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      // Patients erasing themselves no longer have an identity to act as
+      if (patientId === getUserID()) clearUserID();
+      return { data: response?.result?.output, error: null };
+    } catch (err) {
+      console.error('Failed to erase patient data:', err);
+      return { error: toHealthError(err, 'Failed to erase patient data') };
+    }
+  }
+
+  async exportPatientBundle(): Promise<HealthResponse<PatientBundle>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.EXPORT_PATIENT_BUNDLE,
+      argsJson: { patient_id: getUserID() },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.query(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: response?.result?.output, error: null };
+    } catch (err) {
+      console.error('Failed to export patient bundle:', err);
+      return { error: toHealthError(err, 'Failed to export patient bundle') };
+    }
+  }
+
+  async importPatientBundle(bundle: PatientBundle): Promise<HealthResponse<BundleImportSummary>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.IMPORT_PATIENT_BUNDLE,
+      argsJson: { patient_id: getUserID(), bundle },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.execute(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: response?.result?.output, error: null };
+    } catch (err) {
+      console.error('Failed to import patient bundle:', err);
+      return { error: toHealthError(err, 'Failed to import patient bundle') };
+    }
+  }
+
+  async verifyRecordIntegrity(
+    recordId: string,
+    entityId: string = getUserID()
+  ): Promise<HealthResponse<AccessResult<IntegrityReport>>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.VERIFY_RECORD_INTEGRITY,
+      argsJson: { record_id: recordId, entity_id: entityId },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      // Execute rather than query: verification is recorded in the access log
+      const response = await this.rpcClient.execute(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: response?.result?.output, error: null };
+    } catch (err) {
+      console.error('Failed to verify record integrity:', err);
+      return { error: toHealthError(err, 'Failed to verify record integrity') };
+    }
+  }
+
+  // Records the Starknet transaction in which `account` anchored the
+  // record's current hash
+  async anchorRecordHash(
+    recordId: string,
+    txHash: string,
+    account: string
+  ): Promise<HealthResponse<IntegrityAnchor>> {
+    const { jwtObject, config, error } = this.getConfigAndJwt();
+    if (error) return { error };
+
+    const requestParams = {
+      contextId: jwtObject?.context_id ?? getContextId(),
+      method: ClientMethod.ANCHOR_RECORD_HASH,
+      argsJson: { record_id: recordId, tx_hash: txHash, account },
+      executorPublicKey: jwtObject.executor_public_key,
+    };
+
+    try {
+      const response = await this.rpcClient.execute(requestParams, config);
+      if (response?.error) return { error: toHealthError(response.error) };
+      return { data: response?.result?.output, error: null };
+    } catch (err) {
+      console.error('Failed to anchor record hash:', err);
+      return { error: toHealthError(err, 'Failed to anchor record hash') };
+    }
+  }
+}
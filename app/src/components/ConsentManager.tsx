@@ -1,7 +1,8 @@
 import React, { useEffect, useMemo, useState } from 'react';
 import { Card, Button, Input } from '../components/shared/Card';
 import styled from 'styled-components';
-import { HealthDataApi } from '../api/healthDataApi';
+import { HealthDataApi, ResearchPool } from '../api/healthDataApi';
+import { PatientRecord } from '../types/HealthTypes';
 import { useTheme } from '../contexts/ThemeContext';
 import { generateConsentProof } from '../utils/ProofGeneration';
 import { getUserID } from '../utils/UserIdGenerate';
@@ -151,6 +152,14 @@ const StyledSelect = styled.select<{ theme: 'light' | 'dark' }>`
   width: 200px;
 `;
 
+const RecordOption = styled.label<{ theme: 'light' | 'dark' }>`
+  display: flex;
+  align-items: center;
+  gap: 0.5rem;
+  margin-top: 0.5rem;
+  color: ${({ theme }) => (theme === 'light' ? '#111111' : '#ffffff')};
+`;
+
 // Add new styled components
 const TransactionStatus = styled.div<{ status: string; theme: 'light' | 'dark' }>`
   margin-top: 1rem;
@@ -178,6 +187,33 @@ export function ConsentManager() {
   const [reason, setReason] = useState<typeof CONSENT_REASONS[keyof typeof CONSENT_REASONS]>(CONSENT_REASONS.HOSPITAL);
   const [txStatus, setTxStatus] = useState<'none' | 'pending' | 'success' | 'error'>('none');
   const userId = getUserID();
+  const [pools, setPools] = useState<ResearchPool[]>([]);
+  const [records, setRecords] = useState<PatientRecord[]>([]);
+  const [poolId, setPoolId] = useState('');
+  const [selectedRecords, setSelectedRecords] = useState<string[]>([]);
+
+  // Research access means joining one of the entity's pools with chosen records
+  useEffect(() => {
+    if (reason !== CONSENT_REASONS.RESEARCH || !entityId) {
+      setPools([]);
+      setPoolId('');
+      return;
+    }
+    api.listResearchPools().then((result) => {
+      const entityPools = (result.data ?? []).filter((pool) => pool.entity_id === entityId);
+      setPools(entityPools);
+      setPoolId(entityPools[0]?.pool_id ?? '');
+    });
+    api.getPatientRecords().then((result) => setRecords(result.data ?? []));
+  }, [reason, entityId]);
+
+  function toggleRecord(recordId: string) {
+    setSelectedRecords((selected) =>
+      selected.includes(recordId)
+        ? selected.filter((id) => id !== recordId)
+        : [...selected, recordId]
+    );
+  }
 
     const contractAddress =
         '0x01fef8db26d72596018cb1783bb856123099b1a8efac4454c7976171612d0dba';
@@ -242,6 +278,11 @@ export function ConsentManager() {
       if (!patientId) {
         throw new Error('Patient ID not found');
       }
+      if (reason === CONSENT_REASONS.RESEARCH && (!poolId || selectedRecords.length === 0)) {
+        setProofStatus('none');
+        alert('Choose a research pool and at least one record to share');
+        return;
+      }
   
       const data = await generateConsentProof(patientId, entityId, reason);
       setProofData(data);
@@ -250,7 +291,7 @@ export function ConsentManager() {
       // Submit to research pool if research access selected
       if (reason === CONSENT_REASONS.RESEARCH) {
         setTxStatus('pending');
-        const poolResult = await api.submitToPool(entityId);
+        const poolResult = await api.submitToPool(poolId, selectedRecords);
         if (poolResult.error) {
           throw new Error(poolResult.error.message);
         }
@@ -258,7 +299,19 @@ export function ConsentManager() {
   
       // Add consent and trigger transaction
       try {
-        await api.addConsent(entityId, reason, data.proof);
+        const consentResult = await api.addConsent(entityId, reason, data.proof);
+        if (consentResult.error) {
+          throw new Error(consentResult.error.message);
+        }
+        // A consent only covers reads for its purpose; hospitals also need
+        // the records themselves
+        if (reason === CONSENT_REASONS.HOSPITAL) {
+          const expiresAt = Math.floor(Date.now() / 1000) + 90 * 24 * 60 * 60;
+          const grantResult = await api.grantAccess(entityId, expiresAt);
+          if (grantResult.error) {
+            throw new Error(grantResult.error.message);
+          }
+        }
         setTxStatus('pending');
         await writeAsync();
         setTxStatus('success');
@@ -307,6 +360,36 @@ export function ConsentManager() {
         </StyledSelect>
       </InputWrapper>
 
+      {reason === CONSENT_REASONS.RESEARCH && entityId && (
+        <ProofSection theme={theme}>
+          {pools.length === 0 ? (
+            <ToggleText theme={theme}>This entity has no open research pools.</ToggleText>
+          ) : (
+            <StyledSelect
+              theme={theme}
+              value={poolId}
+              onChange={(e) => setPoolId(e.target.value)}
+            >
+              {pools.map((pool) => (
+                <option key={pool.pool_id} value={pool.pool_id}>
+                  {pool.title}
+                </option>
+              ))}
+            </StyledSelect>
+          )}
+          {records.map((record) => (
+            <RecordOption key={record.record_id} theme={theme}>
+              <input
+                type="checkbox"
+                checked={selectedRecords.includes(record.record_id)}
+                onChange={() => toggleRecord(record.record_id)}
+              />
+              {record.record_type} · {new Date(record.timestamp).toLocaleDateString()}
+            </RecordOption>
+          ))}
+        </ProofSection>
+      )}
+
       <ProofSection theme={theme}>
         {/* Remove toggle, always require proof */}
         <Button 
@@ -11,7 +11,7 @@ import { clearApplicationId, getJWTObject } from '../utils/storage';
 import { useAccount, useConnect } from '@starknet-react/core';
 import { useAuth } from '../contexts/AuthContext';
 
-import { getUserID } from '../utils/UserIdGenerate';
+import { clearUserID, getUserID } from '../utils/UserIdGenerate';
 const Nav = styled.nav<{ theme: 'light' | 'dark' }>`
   background: ${({ theme }) =>
     theme === 'light' ? 'rgba(255, 255, 255, 0.8)' : 'rgba(17, 17, 17, 0.8)'};
@@ -252,6 +252,7 @@ export default function Navigation() {
     clearAppEndpoint();
     clearJWT();
     clearApplicationId();
+    clearUserID();
     navigate('/auth');
   };
 
@@ -1,6 +1,7 @@
 import React from 'react';
 import { Navigate } from 'react-router-dom';
 import { getAccessToken } from '@calimero-network/calimero-client';
+import { getUserID } from '../utils/UserIdGenerate';
 
 interface ProtectedRouteProps {
   children: React.ReactElement;
@@ -15,6 +16,11 @@ export default function ProtectedRoute({ children }: ProtectedRouteProps) {
     return <Navigate to="/auth" replace />;
   }
 
+  // Every contract call acts as the registered identity, so register first
+  if (!getUserID()) {
+    return <Navigate to="/role-selection" replace />;
+  }
+
   // Render child components if authenticated
   return children;
 }
\ No newline at end of file
@@ -0,0 +1,129 @@
+import React, { useState } from 'react';
+import styled from 'styled-components';
+import { cairo, type Abi } from 'starknet';
+import { useAccount, useContract, useSendTransaction } from '@starknet-react/core';
+import { Button } from './shared/Card';
+import { ABI } from '../abi/Contract_ABI';
+import { HealthDataApi, IntegrityReport } from '../api/healthDataApi';
+
+const contractAddress = '0x01fef8db26d72596018cb1783bb856123099b1a8efac4454c7976171612d0dba';
+
+const AnchorActions = styled.div`
+  display: flex;
+  gap: 0.5rem;
+  align-items: center;
+  flex-wrap: wrap;
+  margin-top: 0.75rem;
+
+  span {
+    font-size: 0.875rem;
+  }
+`;
+
+interface Props {
+  recordId: string;
+}
+
+// Anchors the record's current hash on Starknet and checks it against the
+// chain. The context cannot reach Starknet, so both checks happen here.
+export function RecordAnchor({ recordId }: Props) {
+  const api = new HealthDataApi();
+  const { address } = useAccount();
+  const { contract } = useContract({ abi: ABI as Abi, address: contractAddress });
+  const { sendAsync } = useSendTransaction({});
+  const [status, setStatus] = useState<string | null>(null);
+  const [busy, setBusy] = useState(false);
+
+  async function integrityReport(): Promise<IntegrityReport | null> {
+    const result = await api.verifyRecordIntegrity(recordId);
+    if (result.error) {
+      setStatus(result.error.message);
+      return null;
+    }
+    if (result.data.status === 'denied') {
+      setStatus(`Cannot verify this record: ${result.data.data}`);
+      return null;
+    }
+    return result.data.data;
+  }
+
+  async function anchor() {
+    if (!contract || !address) {
+      setStatus('Connect a Starknet wallet first');
+      return;
+    }
+    setBusy(true);
+    try {
+      const report = await integrityReport();
+      if (!report) return;
+      if (!report.intact) {
+        setStatus('The record no longer matches its stored hash; not anchoring it');
+        return;
+      }
+
+      const key = report.chain_key;
+      const call = contract.populate('anchor_record_hash', [
+        key.context_id,
+        key.record_id,
+        key.version,
+        cairo.uint256(key.content_hash),
+      ]);
+      const { transaction_hash } = await sendAsync([call]);
+
+      const saved = await api.anchorRecordHash(recordId, transaction_hash, address);
+      setStatus(saved.error ? saved.error.message : `Version ${key.version} anchored in ${transaction_hash}`);
+    } catch (err) {
+      console.error('Failed to anchor record hash:', err);
+      setStatus('Failed to anchor the record hash');
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  async function verify() {
+    if (!contract) return;
+    setBusy(true);
+    try {
+      const report = await integrityReport();
+      if (!report) return;
+      if (!report.anchor) {
+        setStatus(`Version ${report.version} has not been anchored`);
+        return;
+      }
+
+      const key = report.chain_key;
+      const onChain = BigInt(
+        (await contract.call('get_record_anchor', [
+          report.anchor.account,
+          key.context_id,
+          key.record_id,
+          key.version,
+        ])) as bigint
+      );
+      if (onChain === BigInt(0)) {
+        setStatus('No anchor on Starknet yet; the transaction may still be pending');
+      } else if (onChain === BigInt(key.content_hash)) {
+        setStatus('Matches the hash anchored on Starknet');
+      } else {
+        setStatus('Does NOT match the hash anchored on Starknet');
+      }
+    } catch (err) {
+      console.error('Failed to read the record anchor:', err);
+      setStatus('Failed to read the anchor from Starknet');
+    } finally {
+      setBusy(false);
+    }
+  }
+
+  return (
+    <AnchorActions>
+      <Button className="secondary" onClick={anchor} disabled={busy}>
+        Anchor on Starknet
+      </Button>
+      <Button className="secondary" onClick={verify} disabled={busy}>
+        Check anchor
+      </Button>
+      {status && <span>{status}</span>}
+    </AnchorActions>
+  );
+}
@@ -4,8 +4,10 @@ import styled from 'styled-components';
 import { PatientRecord } from '../types/HealthTypes';
 import { useTheme } from '../contexts/ThemeContext';
 import { HealthDataApi } from '../api/healthDataApi';
+import { RecordAnchor } from './RecordAnchor';
 
 interface PatientRecord {
+  record_id: string;
   data: string;  // This contains the JSON string of medical details
   timestamp: number;
   record_type: string;
@@ -300,6 +302,7 @@ export function RecordList({ records, onDelete, onUpdate }: Props) {
                 {record.authorized_ids.length > 0 && (
                   <p><strong>Authorized Users:</strong> {record.authorized_ids.join(', ')}</p>
                 )}
+                <RecordAnchor recordId={record.record_id} />
               </div>
 
               <div className="record-actions">
@@ -5,7 +5,7 @@ import { HealthDataApi } from '../../api/healthDataApi';
 import { PatientRecord } from '../../types/HealthTypes';
 import { LoadingState } from '../../components/shared/LoadingState';
 import { useTheme } from '../../contexts/ThemeContext';
-import { getJWTObject } from '../../utils/storage';
+import { getUserID } from '../../utils/UserIdGenerate';
 
 const CardStyles = `
   background: ${({ theme }) =>
@@ -191,7 +191,6 @@ export function HospitalDashboard() {
   const [error, setError] = useState<string | null>(null);
   const [patientId, setPatientId] = useState('');
   const api = new HealthDataApi();
-  const jwtObject = getJWTObject();
 
   const parseMedicalDetails = (data: string): MedicalDetails | null => {
     try {
@@ -340,7 +339,8 @@ export function HospitalDashboard() {
                         try {
                           const result = await api.accessPatientData(
                             record.owner_id,
-                            jwtObject.executor_public_key
+                            getUserID(),
+                            'hospital_access'
                           );
                           if (result.error) {
                             setError(result.error.message);
@@ -1,8 +1,19 @@
-import React from 'react';
+import React, { useState } from 'react';
 import { useNavigate } from 'react-router-dom';
 import styled from 'styled-components';
 import { useAuth } from '../../contexts/AuthContext';
 import { useTheme } from '../../contexts/ThemeContext';
+import { HealthDataApi, IdentityKind } from '../../api/healthDataApi';
+import ErrorMessage from '../../components/ErrorMessage';
+
+type UserRole = 'patient' | 'hospital' | 'researcher';
+
+// Identity kind registered in the context for each app role
+const KIND_FOR_ROLE: Record<UserRole, IdentityKind> = {
+  patient: 'Patient',
+  hospital: 'Provider',
+  researcher: 'ResearchEntity',
+};
 
 const Container = styled.div<{ theme: 'light' | 'dark' }>`
   min-height: 100vh;
@@ -64,30 +75,132 @@ const RoleButton = styled.button<{ theme: 'light' | 'dark' }>`
   }
 `;
 
+const RegisterForm = styled.form<{ theme: 'light' | 'dark' }>`
+  display: flex;
+  flex-direction: column;
+  gap: 1rem;
+  margin-top: 2rem;
+
+  label {
+    display: flex;
+    flex-direction: column;
+    gap: 0.25rem;
+    font-size: 0.875rem;
+  }
+
+  input {
+    padding: 0.75rem;
+    border-radius: 8px;
+    border: 1px solid ${({ theme }) =>
+      theme === 'light' ? 'rgba(0, 0, 0, 0.1)' : 'rgba(255, 255, 255, 0.1)'};
+    background: transparent;
+    color: inherit;
+  }
+
+  p {
+    font-size: 0.875rem;
+    opacity: 0.8;
+  }
+`;
+
+const api = new HealthDataApi();
+
 export function RoleSelection() {
   const { theme } = useTheme();
   const { login } = useAuth();
   const navigate = useNavigate();
+  const [pendingRole, setPendingRole] = useState<UserRole | null>(null);
+  const [identityId, setIdentityId] = useState('');
+  const [displayName, setDisplayName] = useState('');
+  const [error, setError] = useState<string | null>(null);
+  const [submitting, setSubmitting] = useState(false);
+
+  const enter = (registeredId: string, role: UserRole) => {
+    login(registeredId, role);
+    navigate('/home');
+  };
+
+  // Keys that already have an identity go straight in; new keys register first
+  const handleRoleSelect = async (role: UserRole) => {
+    setError(null);
+    const result = await api.whoami();
+    if (result.error) {
+      setError(result.error.message);
+      return;
+    }
+    const profile = result.data;
+    if (!profile) {
+      setPendingRole(role);
+      return;
+    }
+    if (profile.kind !== KIND_FOR_ROLE[role]) {
+      setError(`This key is registered as ${profile.identity_id} (${profile.kind}).`);
+      return;
+    }
+    enter(profile.identity_id, role);
+  };
+
+  const handleRegister = async (e: React.FormEvent) => {
+    e.preventDefault();
+    if (!pendingRole || !identityId.trim()) return;
 
-  const handleRoleSelect = (role: 'patient' | 'hospital' | 'researcher') => {
-    // Generate a unique ID for the user
-    const uniqueId = `${role}_${Date.now()}`;
-    login(uniqueId, role);
-    
-    // Redirect based on role
-    switch(role) {
-      case 'patient':
-        navigate('/home');
-        break;
-      case 'hospital':
-        navigate('/home');
-        break;
-      case 'researcher':
-        navigate('/home');
-        break;
+    setSubmitting(true);
+    setError(null);
+    const result = await api.registerIdentity(
+      identityId.trim(),
+      KIND_FOR_ROLE[pendingRole],
+      displayName.trim() || identityId.trim()
+    );
+    setSubmitting(false);
+
+    if (result.error) {
+      setError(
+        result.error.code === 'ALREADY_EXISTS'
+          ? `${identityId.trim()} is already taken, or this key is already registered.`
+          : result.error.message
+      );
+      return;
     }
+    enter(result.data.identity_id, pendingRole);
   };
 
+  if (pendingRole) {
+    return (
+      <Container theme={theme}>
+        <RoleCard theme={theme}>
+          <h2>Register Your Identity</h2>
+          <RegisterForm theme={theme} onSubmit={handleRegister}>
+            <label>
+              Identity ID
+              <input
+                value={identityId}
+                onChange={(e) => setIdentityId(e.target.value)}
+                placeholder="e.g. st-marys-hospital"
+                pattern="[A-Za-z0-9._\-]{1,64}"
+                title="Up to 64 letters, digits, '.', '_' or '-'"
+                required
+              />
+            </label>
+            <label>
+              Display name
+              <input
+                value={displayName}
+                onChange={(e) => setDisplayName(e.target.value)}
+              />
+            </label>
+            {pendingRole !== 'patient' && (
+              <p>An administrator has to assign your role before you can access patient data or run pools.</p>
+            )}
+            <ErrorMessage message={error} />
+            <RoleButton theme={theme} type="submit" disabled={submitting}>
+              <h3>{submitting ? 'Registering...' : 'Register'}</h3>
+            </RoleButton>
+          </RegisterForm>
+        </RoleCard>
+      </Container>
+    );
+  }
+
   return (
     <Container theme={theme}>
       <RoleCard theme={theme}>
@@ -117,6 +230,7 @@ export function RoleSelection() {
             <p>Create reward pools and access anonymized data</p>
           </RoleButton>
         </RoleGrid>
+        <ErrorMessage message={error} />
       </RoleCard>
     </Container>
   );
@@ -67,10 +67,10 @@ const SubmissionCard = styled(Card)<{ theme: 'light' | 'dark' }>`
 
 const StatusBadge = styled.span<{
   theme: 'light' | 'dark';
-  status: 'pending' | 'approved' | 'rejected';
+  status: 'pending' | 'accepted' | 'rejected' | 'withdrawn' | 'rewarded';
 }>`
   background: ${({ status }) =>
-    status === 'approved'
+    status === 'accepted'
       ? '#10B981'
       : status === 'rejected'
         ? '#EF4444'
@@ -169,11 +169,12 @@ const ConfirmDialog = styled(ReportModal)`
 interface ResearchSubmission {
   patientId: string;
   timestamp: number;
-  status: 'pending' | 'approved' | 'rejected';
+  status: 'pending' | 'accepted' | 'rejected' | 'withdrawn' | 'rewarded';
   dataHash: string;
 }
 
 interface ResearchPool {
+  pool_id: string;
   title: string;
   description: string;
   reward_amount: number;
@@ -369,6 +370,16 @@ export function RewardPoolCreator() {
           if (result.error) {
             throw new Error(result.error.message);
           }
+
+          // Credit the Starknet deposit to the pool so it can reserve rewards
+          const funded = await api.fundPool(
+            result.data,
+            Math.floor(parseFloat(rewardAmount)),
+            writeData.transaction_hash
+          );
+          if (funded.error) {
+            throw new Error(funded.error.message);
+          }
   
           // Reset form
           setTitle('');
@@ -397,9 +408,9 @@ export function RewardPoolCreator() {
   const fetchPools = async () => {
     setIsLoading(true);
     try {
-      const result = await api.getResearchPool();
+      const result = await api.listEntityPools();
       if (result.data) {
-        setPools([result.data]); // API returns single pool, wrap in array
+        setPools(result.data);
       }
     } catch (err) {
       console.error('Failed to fetch pools:', err);
@@ -415,7 +426,7 @@ export function RewardPoolCreator() {
     try {
       setIsLoading(true);
 
-      // Get current pool's entity ID
+      // Get current pool's ID
       const currentPool = pools[0]; // Assuming we're working with the first pool
       if (!currentPool) {
         throw new Error('No active research pool found');
@@ -423,9 +434,9 @@ export function RewardPoolCreator() {
 
       // Update submission status
       const result = await api.updateSubmissionStatus(
-        currentPool.entity_id,
+        currentPool.pool_id,
         patientId,
-        'approved',
+        'accepted',
       );
 
       if (result.error) {
@@ -442,9 +453,9 @@ export function RewardPoolCreator() {
     }
   };
 
-  const handleDeletePool = (entityId: string) => {
-    console.log('Deleting pool:', entityId);
-    setPoolToDelete(entityId);
+  const handleDeletePool = (poolId: string) => {
+    console.log('Deleting pool:', poolId);
+    setPoolToDelete(poolId);
     setShowDeleteConfirm(true);
   };
 
@@ -459,7 +470,7 @@ export function RewardPoolCreator() {
       }
 
       // Remove pool from state
-      setPools(pools.filter((p) => p.entity_id !== poolToDelete));
+      setPools(pools.filter((p) => p.pool_id !== poolToDelete));
       setShowDeleteConfirm(false);
     } catch (err) {
       console.error('Failed to delete pool:', err);
@@ -531,7 +542,7 @@ export function RewardPoolCreator() {
           <LoadingState message="Loading pools..." />
         ) : (
           pools.map((pool) => (
-            <FormCard key={pool.entity_id} theme={theme}>
+            <FormCard key={pool.pool_id} theme={theme}>
               <h3>{pool.title}</h3>
               <p>{pool.description}</p>
               <p>Reward: {pool.reward_amount} STARK</p>
@@ -540,7 +551,7 @@ export function RewardPoolCreator() {
 
               <ButtonGroup>
                 <DeleteButton
-                  onClick={() => handleDeletePool(pool.entity_id)}
+                  onClick={() => handleDeletePool(pool.pool_id)}
                   disabled={isDeleting}
                 >
                   {isDeleting ? 'Deleting...' : 'Delete Pool'}
@@ -60,7 +60,7 @@ const StatusBadge = styled.span<{
   status: string;
 }>`
   background: ${({ status }) =>
-    status === 'approved'
+    status === 'accepted'
       ? '#10B981'
       : status === 'rejected'
         ? '#EF4444'
@@ -357,7 +357,7 @@ export function RewardPoolUser() {
                     {pool.status.charAt(0).toUpperCase() + pool.status.slice(1).toLowerCase()}
                   </StatusBadge>
                 </div>
-                {pool.status.toLowerCase() === 'approved' && (
+                {pool.status.toLowerCase() === 'accepted' && (
                   <StyledButton
                     variant="primary"
                     onClick={() => handleClaimReward(pool.entity_id)}
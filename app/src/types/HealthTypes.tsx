@@ -1,5 +1,6 @@
 // src/types/HealthTypes.ts
 export interface PatientRecord {
+  record_id: string;
   data: string;
   timestamp: number;
   record_type: string;
@@ -13,4 +14,33 @@ export interface ConsentPolicy {
   entity_id: string;
   purpose: string;
   expiration: number;
-}
\ No newline at end of file
+}
+
+export type HealthErrorCode =
+  | 'NOT_FOUND'
+  | 'ALREADY_EXISTS'
+  | 'EXPIRED'
+  | 'INVALID_INPUT'
+  | 'INVALID_STATE'
+  | 'UNAUTHORIZED'
+  | 'MISSING_ROLE'
+  | 'UNREGISTERED_KEY'
+  | 'INVALID_KEY'
+  | 'CONSENT_MISSING'
+  | 'NOT_ELIGIBLE'
+  | 'STORAGE_ERROR'
+  // Set by the client when the call never reached the contract
+  | 'UNAUTHENTICATED'
+  | 'REQUEST_FAILED';
+
+// Error payload returned by every contract method
+export interface HealthError {
+  code: HealthErrorCode;
+  message: string;
+}
+
+// Result of a HealthDataApi call. Switch on `error.code`; messages are for humans.
+export interface HealthResponse<T> {
+  data?: T;
+  error?: HealthError | null;
+}
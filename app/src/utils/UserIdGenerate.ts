@@ -1,11 +1,39 @@
 import { getJWTObject } from './storage';
-import { hash } from 'starknet';
 
+const IDENTITY_KEY = 'registered-identity';
+
+interface CachedIdentity {
+  executor_public_key: string;
+  identity_id: string;
+}
+
+// Identity ID the current executor key is registered as in the context,
+// cached by HealthDataApi.whoami/registerIdentity. Empty until the key is
+// registered. Contract methods take this ID as patient_id / entity_id.
 export function getUserID(): string {
-    const jwtObject = getJWTObject();
-    if (jwtObject) {
-        const hashedId = hash.getSelectorFromName(jwtObject.executor_public_key);
-        return hashedId.substring(0, 31);
-    }
+  const jwtObject = getJWTObject();
+  const stored = localStorage.getItem(IDENTITY_KEY);
+  if (!jwtObject || !stored) {
+    return '';
+  }
+  try {
+    const cached: CachedIdentity = JSON.parse(stored);
+    return cached.executor_public_key === jwtObject.executor_public_key ? cached.identity_id : '';
+  } catch (e) {
     return '';
-}
\ No newline at end of file
+  }
+}
+
+export function setUserID(identityId: string) {
+  const jwtObject = getJWTObject();
+  if (!jwtObject) return;
+  const cached: CachedIdentity = {
+    executor_public_key: jwtObject.executor_public_key,
+    identity_id: identityId,
+  };
+  localStorage.setItem(IDENTITY_KEY, JSON.stringify(cached));
+}
+
+export function clearUserID() {
+  localStorage.removeItem(IDENTITY_KEY);
+}
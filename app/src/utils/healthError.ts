@@ -0,0 +1,55 @@
+import { HealthError, HealthErrorCode } from '../types/HealthTypes';
+
+const CONTRACT_ERROR_CODES: HealthErrorCode[] = [
+  'NOT_FOUND',
+  'ALREADY_EXISTS',
+  'EXPIRED',
+  'INVALID_INPUT',
+  'INVALID_STATE',
+  'UNAUTHORIZED',
+  'MISSING_ROLE',
+  'UNREGISTERED_KEY',
+  'INVALID_KEY',
+  'CONSENT_MISSING',
+  'NOT_ELIGIBLE',
+  'STORAGE_ERROR',
+];
+
+function isHealthError(value: unknown): value is HealthError {
+  const candidate = value as HealthError;
+  return (
+    typeof value === 'object' &&
+    value !== null &&
+    CONTRACT_ERROR_CODES.includes(candidate.code) &&
+    typeof candidate.message === 'string'
+  );
+}
+
+// Finds the `{ code, message }` error a contract method returned inside an
+// RPC error. The node forwards it as JSON, either as a string or as bytes,
+// nested in the transport error. Anything else becomes REQUEST_FAILED.
+export function toHealthError(error: unknown, fallbackMessage = 'Request failed'): HealthError {
+  const pending: unknown[] = [error];
+  while (pending.length > 0) {
+    const value = pending.pop();
+    if (isHealthError(value)) {
+      return { code: value.code, message: value.message };
+    }
+    if (typeof value === 'string') {
+      try {
+        pending.push(JSON.parse(value));
+      } catch (e) {
+        // Not JSON, keep looking
+      }
+    } else if (Array.isArray(value) && value.length > 0 && value.every((b) => typeof b === 'number')) {
+      try {
+        pending.push(JSON.parse(new TextDecoder().decode(new Uint8Array(value))));
+      } catch (e) {
+        // Not a JSON payload
+      }
+    } else if (typeof value === 'object' && value !== null) {
+      pending.push(...Object.values(value));
+    }
+  }
+  return { code: 'REQUEST_FAILED', message: fallbackMessage };
+}
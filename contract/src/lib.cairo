@@ -52,6 +52,20 @@ pub trait IRewardPool<TContractState> {
     fn add_participant(ref self: TContractState,entity_id: felt252, participant: ContractAddress );
 }
 
+// Record hashes anchored by patients, keyed by the anchoring account.
+// `context_id` and `record_id` are the first 31 bytes of the SHA-256 of the
+// base58 Calimero context ID and of the record ID, and `content_hash` is the
+// SHA-256 of the record payload; the context reports all three for a version.
+#[starknet::interface]
+pub trait IRecordIntegrity<TContractState> {
+    fn anchor_record_hash(
+        ref self: TContractState, context_id: felt252, record_id: felt252, version: u32, content_hash: u256
+    );
+    fn get_record_anchor(
+        self: @TContractState, anchored_by: ContractAddress, context_id: felt252, record_id: felt252, version: u32
+    ) -> u256;
+}
+
 
 #[derive(Drop, Serde, starknet::Store)]
 pub struct Person {
@@ -93,7 +107,7 @@ pub struct RewardPoolData {
 #[starknet::contract]
 mod health_data {
 
-    use super::{IDataAccessLogging, IConsentManagement, IRewardPool, Person, Consent, DataRequestLog, Expiration,RewardPoolData};
+    use super::{IDataAccessLogging, IConsentManagement, IRewardPool, IRecordIntegrity, Person, Consent, DataRequestLog, Expiration,RewardPoolData};
 
     
     use core::starknet::storage::{
@@ -124,6 +138,9 @@ mod health_data {
             rewards: Map<felt252, u256>, 
             pragma_contract: ContractAddress,
             reward_pools: Map<felt252, RewardPoolData>,
+            // (anchoring account, context_id, record_id, version) -> SHA-256 of the record payload.
+            // Scoped to the caller so nobody can claim a version before its patient does.
+            record_anchors: Map<(ContractAddress, felt252, felt252, u32), u256>,
         }
 
     #[generate_trait]
@@ -144,6 +161,7 @@ mod health_data {
         RewardDeposited: RewardDeposited,
         RewardWithdrawn: RewardWithdrawn,
         ParticipantAdded: ParticipantAdded,
+        RecordHashAnchored: RecordHashAnchored,
     }
 
     #[derive(Drop, starknet::Event)]
@@ -189,6 +207,15 @@ struct ParticipantAdded {
 }
 
 
+    #[derive(Drop, starknet::Event)]
+    struct RecordHashAnchored {
+        context_id: felt252,
+        record_id: felt252,
+        version: u32,
+        content_hash: u256,
+        anchored_by: ContractAddress,
+    }
+
     #[constructor]
 fn constructor(ref self: ContractState, initial_owner: ContractAddress) {
     let owner = Person { 
@@ -389,6 +416,44 @@ fn constructor(ref self: ContractState, initial_owner: ContractAddress) {
 }
     }
 
+    #[abi(embed_v0)]
+    impl RecordIntegrity of IRecordIntegrity<ContractState> {
+        // Anchors are write-once per caller, so a stored hash cannot be replaced
+        // later, and verifiers read the one written by the patient's account
+        fn anchor_record_hash(
+            ref self: ContractState,
+            context_id: felt252,
+            record_id: felt252,
+            version: u32,
+            content_hash: u256
+        ) {
+            assert(content_hash != 0, 'Empty content hash');
+            let caller = get_caller_address();
+            let key = (caller, context_id, record_id, version);
+            assert(self.record_anchors.read(key) == 0, 'Version already anchored');
+
+            self.record_anchors.write(key, content_hash);
+
+            self.emit(Event::RecordHashAnchored(RecordHashAnchored {
+                context_id,
+                record_id,
+                version,
+                content_hash,
+                anchored_by: caller,
+            }));
+        }
+
+        fn get_record_anchor(
+            self: @ContractState,
+            anchored_by: ContractAddress,
+            context_id: felt252,
+            record_id: felt252,
+            version: u32
+        ) -> u256 {
+            self.record_anchors.read((anchored_by, context_id, record_id, version))
+        }
+    }
+
     #[abi(embed_v0)]
     impl DataAccessLogging of IDataAccessLogging<ContractState> {
         fn log_data_request(
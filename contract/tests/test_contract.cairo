@@ -0,0 +1,62 @@
+use starknet::ContractAddress;
+use snforge_std::{
+    declare, ContractClassTrait, DeclareResultTrait, start_cheat_caller_address,
+    stop_cheat_caller_address
+};
+use contract::{IRecordIntegrityDispatcher, IRecordIntegrityDispatcherTrait};
+
+const CONTEXT: felt252 = 'context-1';
+const RECORD: felt252 = 'rec-1';
+const PATIENT_HASH: u256 = 0xabc;
+
+fn address(name: felt252) -> ContractAddress {
+    name.try_into().unwrap()
+}
+
+fn deploy() -> IRecordIntegrityDispatcher {
+    let contract = declare("health_data").unwrap().contract_class();
+    let calldata = array![address('owner').into()];
+    let (contract_address, _) = contract.deploy(@calldata).unwrap();
+    IRecordIntegrityDispatcher { contract_address }
+}
+
+fn anchor_as(
+    dispatcher: IRecordIntegrityDispatcher, caller: ContractAddress, version: u32, content_hash: u256
+) {
+    start_cheat_caller_address(dispatcher.contract_address, caller);
+    dispatcher.anchor_record_hash(CONTEXT, RECORD, version, content_hash);
+    stop_cheat_caller_address(dispatcher.contract_address);
+}
+
+#[test]
+fn anchors_are_scoped_to_the_anchoring_account() {
+    let dispatcher = deploy();
+    let patient = address('patient');
+    let squatter = address('squatter');
+
+    // Anchoring first does not block the patient or change what they anchored
+    anchor_as(dispatcher, squatter, 1, 0xbad);
+    anchor_as(dispatcher, patient, 1, PATIENT_HASH);
+
+    assert(dispatcher.get_record_anchor(patient, CONTEXT, RECORD, 1) == PATIENT_HASH, 'Wrong patient anchor');
+    assert(dispatcher.get_record_anchor(squatter, CONTEXT, RECORD, 1) == 0xbad, 'Wrong squatter anchor');
+    assert(dispatcher.get_record_anchor(patient, 'context-2', RECORD, 1) == 0, 'Anchor leaked across contexts');
+    assert(dispatcher.get_record_anchor(patient, CONTEXT, RECORD, 2) == 0, 'Anchor leaked across versions');
+}
+
+#[test]
+#[should_panic(expected: ('Version already anchored',))]
+fn anchors_are_write_once() {
+    let dispatcher = deploy();
+    let patient = address('patient');
+
+    anchor_as(dispatcher, patient, 1, PATIENT_HASH);
+    anchor_as(dispatcher, patient, 1, 0xdef);
+}
+
+#[test]
+#[should_panic(expected: ('Empty content hash',))]
+fn empty_hashes_are_rejected() {
+    let dispatcher = deploy();
+    anchor_as(dispatcher, address('patient'), 1, 0);
+}
@@ -0,0 +1,173 @@
+//! De-identification of structured record payloads.
+//!
+//! Records whose payload is plaintext JSON can be turned into a research
+//! derivative inside the context: direct identifiers are dropped, subject
+//! references are replaced by a pseudonym and ISO dates are shifted by a
+//! per-subject offset so intervals between events survive. Encrypted
+//! payloads cannot be processed here; patients register a derivative they
+//! produced client-side instead.
+//!
+//! FHIR resources get the same treatment plus their own identifiers:
+//! resource and element `id`s and the narrative `div` are dropped,
+//! `Patient/...` references point at the pseudonym, references to anything
+//! else are dropped, and `display` texts are only kept on codings.
+
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+
+use crate::time::{civil_from_days, days_from_civil};
+
+/// Keys removed outright, compared case-insensitively with `_` and `-` ignored.
+const IDENTIFIER_KEYS: &[&str] = &[
+    "name", "fullname", "firstname", "lastname", "given", "family", "prefix", "suffix",
+    "address", "street", "city", "postalcode", "zip", "zipcode",
+    "phone", "telecom", "fax", "email", "contact",
+    "ssn", "socialsecuritynumber", "mrn", "medicalrecordnumber", "identifier",
+    "insuranceid", "accountnumber", "licensenumber", "ipaddress", "photo",
+    // FHIR: resource and element ids, narrative XHTML, annotation authors
+    "id", "div", "authorstring",
+];
+
+/// Keys whose value identifies the subject and is replaced by the pseudonym.
+const SUBJECT_KEYS: &[&str] = &["patientid", "subjectid", "ownerid", "personid"];
+
+/// Keys holding a birth date, reduced to the (shifted) year.
+const BIRTH_DATE_KEYS: &[&str] = &["birthdate", "dateofbirth", "dob"];
+
+/// Largest date shift applied in either direction.
+const MAX_SHIFT_DAYS: i64 = 365;
+
+/// Keyed SHA-256 over `parts`, hex encoded. Parts are length-prefixed so
+/// `("ab", "c")` and `("a", "bc")` never collide.
+pub fn keyed_hash(key: &[u8], parts: &[&str]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(key);
+    for part in parts {
+        hasher.update((part.len() as u64).to_le_bytes());
+        hasher.update(part.as_bytes());
+    }
+    hex::encode(hasher.finalize())
+}
+
+/// Stable pseudonymous subject ID for a patient.
+pub fn subject_pseudonym(key: &[u8], patient_id: &str) -> String {
+    format!("subj-{}", &keyed_hash(key, &["subject", patient_id])[..32])
+}
+
+/// Participant ID of a patient within one pool. Unlinkable across pools
+/// without the key, so researchers cannot join their pools on it.
+pub fn pool_pseudonym(key: &[u8], pool_id: &str, patient_id: &str) -> String {
+    format!("ppt-{}", &keyed_hash(key, &["pool", pool_id, patient_id])[..32])
+}
+
+/// Stand-in left where a reference to an erased patient has to remain, such
+/// as the audit log. Stable, so erasing twice yields the same tombstone.
+pub fn erasure_tombstone(key: &[u8], patient_id: &str) -> String {
+    format!("erased-{}", &keyed_hash(key, &["erased", patient_id])[..32])
+}
+
+/// ID a pool owner sees for a derivative. Scoped to the pool like the
+/// participant ID, so a record shared with two pools is not linkable by it.
+pub fn pool_derivative_id(key: &[u8], pool_id: &str, anon_id: &str) -> String {
+    format!("anon-{}", &keyed_hash(key, &["pool-derivative", pool_id, anon_id])[..32])
+}
+
+/// Per-subject date offset in days, never zero.
+pub fn date_shift_days(key: &[u8], patient_id: &str) -> i64 {
+    shift_from_digest(&keyed_hash(key, &["date-shift", patient_id]))
+}
+
+/// Date offset of a patient within one pool, so shifted dates cannot be
+/// matched across pools either.
+pub fn pool_date_shift_days(key: &[u8], pool_id: &str, patient_id: &str) -> i64 {
+    shift_from_digest(&keyed_hash(key, &["date-shift", pool_id, patient_id]))
+}
+
+fn shift_from_digest(digest: &str) -> i64 {
+    let raw = u64::from_str_radix(&digest[..8], 16).unwrap_or(1);
+    let magnitude = (raw % MAX_SHIFT_DAYS as u64) as i64 + 1;
+    if raw & (1 << 31) == 0 { magnitude } else { -magnitude }
+}
+
+/// De-identifies a JSON payload. Returns `None` when the payload is not JSON,
+/// which is the case for encrypted records.
+pub fn deidentify_json(data: &[u8], subject: &str, shift_days: i64) -> Option<Vec<u8>> {
+    let mut value: Value = serde_json::from_slice(data).ok()?;
+    scrub(&mut value, subject, shift_days);
+    serde_json::to_vec(&value).ok()
+}
+
+fn normalize_key(key: &str) -> String {
+    key.chars()
+        .filter(|c| *c != '_' && *c != '-')
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+/// Whether a FHIR `reference` points at a Patient, relative or absolute.
+fn is_patient_reference(reference: &Value) -> bool {
+    reference
+        .as_str()
+        .is_some_and(|reference| reference.starts_with("Patient/") || reference.contains("/Patient/"))
+}
+
+fn scrub(value: &mut Value, subject: &str, shift_days: i64) {
+    match value {
+        Value::Object(map) => {
+            // A coding's display names the code; anywhere else, such as on a
+            // reference, it tends to name a person or organisation
+            let is_coding = map.contains_key("code") || map.contains_key("system");
+            map.retain(|key, field| match normalize_key(key).as_str() {
+                "display" => is_coding,
+                "reference" => is_patient_reference(field),
+                key => !IDENTIFIER_KEYS.contains(&key),
+            });
+            for (key, field) in map.iter_mut() {
+                let key = normalize_key(key);
+                if SUBJECT_KEYS.contains(&key.as_str()) {
+                    *field = Value::String(subject.to_string());
+                } else if key == "reference" {
+                    *field = Value::String(format!("Patient/{}", subject));
+                } else if BIRTH_DATE_KEYS.contains(&key.as_str()) {
+                    *field = match field.as_str().and_then(|date| shift_date(date, shift_days)) {
+                        Some(shifted) => Value::String(shifted[..4].to_string()),
+                        None => Value::Null,
+                    };
+                } else {
+                    scrub(field, subject, shift_days);
+                }
+            }
+        }
+        Value::Array(items) => items.iter_mut().for_each(|item| scrub(item, subject, shift_days)),
+        Value::String(text) => {
+            if let Some(shifted) = shift_date(text, shift_days) {
+                *text = shifted;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Shifts a string starting with an ISO `YYYY-MM-DD` date, keeping any time
+/// part that follows. Returns `None` for anything else.
+fn shift_date(text: &str, shift_days: i64) -> Option<String> {
+    let date = text.get(..10)?;
+    let rest = &text[10..];
+    if !(rest.is_empty() || rest.starts_with('T') || rest.starts_with(' ')) {
+        return None;
+    }
+
+    let bytes = date.as_bytes();
+    if bytes[4] != b'-' || bytes[7] != b'-' {
+        return None;
+    }
+    let year: i64 = date[..4].parse().ok()?;
+    let month: u32 = date[5..7].parse().ok()?;
+    let day: u32 = date[8..10].parse().ok()?;
+    if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+        return None;
+    }
+
+    let (y, m, d) = civil_from_days(days_from_civil(year, month, day) + shift_days);
+    Some(format!("{:04}-{:02}-{:02}{}", y, m, d, rest))
+}
@@ -0,0 +1,318 @@
+//! Structured clinical data kept alongside the encrypted record payload.
+//!
+//! Entries are optional: a record may stay an opaque blob, or carry coded
+//! observations, conditions, medications, allergies and encounters that the
+//! context can validate, filter on and de-identify. Dates are ISO 8601
+//! strings (`YYYY-MM-DD`, optionally followed by a time) as in FHIR.
+
+use std::fmt;
+use std::io;
+
+use calimero_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::error::HealthError;
+
+/// Kind of medical record. Unknown names are kept as `Custom`, so clients
+/// can introduce their own types without a contract change.
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub enum RecordType {
+    Observation,
+    Condition,
+    Medication,
+    Allergy,
+    Encounter,
+    Immunization,
+    Procedure,
+    DiagnosticReport,
+    Demographics,
+    #[default]
+    Document,
+    Custom(String),
+}
+
+impl RecordType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            RecordType::Observation => "observation",
+            RecordType::Condition => "condition",
+            RecordType::Medication => "medication",
+            RecordType::Allergy => "allergy",
+            RecordType::Encounter => "encounter",
+            RecordType::Immunization => "immunization",
+            RecordType::Procedure => "procedure",
+            RecordType::DiagnosticReport => "diagnostic_report",
+            RecordType::Demographics => "demographics",
+            RecordType::Document => "document",
+            RecordType::Custom(name) => name,
+        }
+    }
+
+    fn parse(value: &str) -> Self {
+        match value.trim().to_lowercase().replace([' ', '-'], "_").as_str() {
+            "observation" => RecordType::Observation,
+            "condition" => RecordType::Condition,
+            "medication" => RecordType::Medication,
+            "allergy" => RecordType::Allergy,
+            "encounter" => RecordType::Encounter,
+            "immunization" => RecordType::Immunization,
+            "procedure" => RecordType::Procedure,
+            "diagnostic_report" => RecordType::DiagnosticReport,
+            "demographics" => RecordType::Demographics,
+            "document" => RecordType::Document,
+            _ => RecordType::Custom(value.trim().to_string()),
+        }
+    }
+
+    /// The entry kind every structured entry of this record must have, if
+    /// the type maps to one.
+    fn entry_kind(&self) -> Option<&'static str> {
+        match self {
+            RecordType::Observation => Some("observation"),
+            RecordType::Condition => Some("condition"),
+            RecordType::Medication => Some("medication"),
+            RecordType::Allergy => Some("allergy"),
+            RecordType::Encounter => Some("encounter"),
+            _ => None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), HealthError> {
+        match self {
+            RecordType::Custom(name) if name.is_empty() => {
+                Err(HealthError::invalid_input("record type must not be empty"))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl fmt::Display for RecordType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+// Stored and sent as the plain type name; unknown names become `Custom`.
+impl BorshSerialize for RecordType {
+    fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+        BorshSerialize::serialize(self.as_str(), writer)
+    }
+}
+
+impl BorshDeserialize for RecordType {
+    fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+        Ok(RecordType::parse(&String::deserialize_reader(reader)?))
+    }
+}
+
+impl Serialize for RecordType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for RecordType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(RecordType::parse(&<String as Deserialize>::deserialize(deserializer)?))
+    }
+}
+
+/// A code from a terminology such as LOINC, SNOMED CT, RxNorm or ICD-10.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+pub struct Coding {
+    system: String, // Terminology URI or short name, e.g. "http://loinc.org"
+    code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    display: Option<String>,
+}
+
+impl Coding {
+    pub fn code(&self) -> &str {
+        &self.code
+    }
+
+    fn validate(&self, field: &str) -> Result<(), HealthError> {
+        if self.system.trim().is_empty() || self.code.trim().is_empty() {
+            return Err(HealthError::invalid_input(format!("{} needs both a system and a code", field)));
+        }
+        Ok(())
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum ObservationValue {
+    Quantity { value: f64, unit: String },
+    Coded(Coding),
+    Text(String),
+    Boolean(bool),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum AllergySeverity {
+    Mild,
+    Moderate,
+    Severe,
+}
+
+/// One coded clinical fact.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, PartialEq, Debug)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ClinicalEntry {
+    Observation {
+        code: Coding,
+        value: Option<ObservationValue>,
+        effective: Option<String>,
+    },
+    Condition {
+        code: Coding,
+        clinical_status: Option<String>, // e.g. active, resolved
+        onset: Option<String>,
+        abatement: Option<String>,
+    },
+    Medication {
+        code: Coding,
+        dosage: Option<String>,
+        start: Option<String>,
+        end: Option<String>,
+    },
+    Allergy {
+        substance: Coding,
+        reaction: Option<Coding>,
+        severity: Option<AllergySeverity>,
+    },
+    Encounter {
+        class: Coding,
+        reason: Option<Coding>,
+        start: String,
+        end: Option<String>,
+    },
+}
+
+impl ClinicalEntry {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ClinicalEntry::Observation { .. } => "observation",
+            ClinicalEntry::Condition { .. } => "condition",
+            ClinicalEntry::Medication { .. } => "medication",
+            ClinicalEntry::Allergy { .. } => "allergy",
+            ClinicalEntry::Encounter { .. } => "encounter",
+        }
+    }
+
+    /// Every coding in the entry, for searching by code.
+    pub fn codings(&self) -> Vec<&Coding> {
+        match self {
+            ClinicalEntry::Observation { code, value, .. } => {
+                let mut codings = vec![code];
+                if let Some(ObservationValue::Coded(coded)) = value {
+                    codings.push(coded);
+                }
+                codings
+            }
+            ClinicalEntry::Condition { code, .. } | ClinicalEntry::Medication { code, .. } => vec![code],
+            ClinicalEntry::Allergy { substance, reaction, .. } => {
+                std::iter::once(substance).chain(reaction.as_ref()).collect()
+            }
+            ClinicalEntry::Encounter { class, reason, .. } => {
+                std::iter::once(class).chain(reason.as_ref()).collect()
+            }
+        }
+    }
+
+    fn validate(&self) -> Result<(), HealthError> {
+        match self {
+            ClinicalEntry::Observation { code, value, effective } => {
+                code.validate("observation code")?;
+                match value {
+                    Some(ObservationValue::Quantity { value, unit }) => {
+                        if !value.is_finite() {
+                            return Err(HealthError::invalid_input("observation value must be a finite number"));
+                        }
+                        if unit.trim().is_empty() {
+                            return Err(HealthError::invalid_input("observation quantity needs a unit"));
+                        }
+                    }
+                    Some(ObservationValue::Coded(coded)) => coded.validate("observation value")?,
+                    Some(ObservationValue::Text(_)) | Some(ObservationValue::Boolean(_)) | None => {}
+                }
+                validate_date("observation effective date", effective.as_deref())
+            }
+            ClinicalEntry::Condition { code, onset, abatement, .. } => {
+                code.validate("condition code")?;
+                validate_period("condition", onset.as_deref(), abatement.as_deref())
+            }
+            ClinicalEntry::Medication { code, start, end, .. } => {
+                code.validate("medication code")?;
+                validate_period("medication", start.as_deref(), end.as_deref())
+            }
+            ClinicalEntry::Allergy { substance, reaction, .. } => {
+                substance.validate("allergy substance")?;
+                match reaction {
+                    Some(reaction) => reaction.validate("allergy reaction"),
+                    None => Ok(()),
+                }
+            }
+            ClinicalEntry::Encounter { class, reason, start, end } => {
+                class.validate("encounter class")?;
+                if let Some(reason) = reason {
+                    reason.validate("encounter reason")?;
+                }
+                validate_period("encounter", Some(start), end.as_deref())
+            }
+        }
+    }
+}
+
+/// Checks entries on their own and against the record type.
+pub fn validate_entries(record_type: &RecordType, entries: &[ClinicalEntry]) -> Result<(), HealthError> {
+    record_type.validate()?;
+
+    for entry in entries {
+        if let Some(kind) = record_type.entry_kind() {
+            if entry.kind() != kind {
+                return Err(HealthError::invalid_input(format!(
+                    "a {} record cannot hold a {} entry", record_type, entry.kind()
+                )));
+            }
+        }
+        entry.validate()?;
+    }
+    Ok(())
+}
+
+/// `YYYY-MM-DD` date part of an ISO 8601 value.
+pub fn date_part(value: &str) -> Option<&str> {
+    let date = value.get(..10)?;
+    let bytes = date.as_bytes();
+    let digits = [0, 1, 2, 3, 5, 6, 8, 9].iter().all(|&i| bytes[i].is_ascii_digit());
+    if !digits || bytes[4] != b'-' || bytes[7] != b'-' {
+        return None;
+    }
+    let month: u32 = date[5..7].parse().ok()?;
+    let day: u32 = date[8..10].parse().ok()?;
+    ((1..=12).contains(&month) && (1..=31).contains(&day)).then_some(date)
+}
+
+fn validate_date(field: &str, value: Option<&str>) -> Result<(), HealthError> {
+    match value {
+        Some(value) if date_part(value).is_none() => Err(HealthError::invalid_input(format!(
+            "{} must be an ISO 8601 date, got {:?}", field, value
+        ))),
+        _ => Ok(()),
+    }
+}
+
+fn validate_period(what: &str, start: Option<&str>, end: Option<&str>) -> Result<(), HealthError> {
+    validate_date(&format!("{} start", what), start)?;
+    validate_date(&format!("{} end", what), end)?;
+
+    if let (Some(start), Some(end)) = (start.and_then(date_part), end.and_then(date_part)) {
+        if end < start {
+            return Err(HealthError::invalid_input(format!("{} ends before it starts", what)));
+        }
+    }
+    Ok(())
+}
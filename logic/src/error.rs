@@ -0,0 +1,93 @@
+use calimero_storage::collections::StoreError;
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use thiserror::Error;
+
+use crate::Role;
+
+/// Errors returned by every `#[app::logic]` method.
+///
+/// Serialized as `{ "code": "...", "message": "..." }`. Codes are stable and
+/// meant to be matched by clients; messages are for humans only.
+#[derive(Debug, Error)]
+pub enum HealthError {
+    #[error("{resource} {id} not found")]
+    NotFound { resource: &'static str, id: String },
+    #[error("{resource} {id} already exists")]
+    AlreadyExists { resource: &'static str, id: String },
+    #[error("{resource} {id} has expired")]
+    Expired { resource: &'static str, id: String },
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+    #[error("operation not allowed: {0}")]
+    InvalidState(String),
+    #[error("{caller} is not authorized to act as {acting_as}")]
+    Unauthorized { caller: String, acting_as: String },
+    #[error("{identity_id} lacks one of the required roles {required:?}")]
+    MissingRole { identity_id: String, required: Vec<Role> },
+    #[error("executor key {key} is not bound to any identity")]
+    UnregisteredKey { key: String },
+    #[error("{key} is not a valid base58 or hex public key")]
+    InvalidKey { key: String },
+    #[error("no valid consent from {patient_id} to {entity_id} for purpose {purpose}")]
+    ConsentMissing { patient_id: String, entity_id: String, purpose: String },
+    #[error("not eligible for pool {pool_id}: {reason}")]
+    NotEligible { pool_id: String, reason: String },
+    #[error("storage error: {0}")]
+    Storage(String),
+}
+
+impl HealthError {
+    pub fn not_found(resource: &'static str, id: impl Into<String>) -> Self {
+        HealthError::NotFound { resource, id: id.into() }
+    }
+
+    pub fn already_exists(resource: &'static str, id: impl Into<String>) -> Self {
+        HealthError::AlreadyExists { resource, id: id.into() }
+    }
+
+    pub fn expired(resource: &'static str, id: impl Into<String>) -> Self {
+        HealthError::Expired { resource, id: id.into() }
+    }
+
+    pub fn invalid_input(message: impl Into<String>) -> Self {
+        HealthError::InvalidInput(message.into())
+    }
+
+    pub fn invalid_state(message: impl Into<String>) -> Self {
+        HealthError::InvalidState(message.into())
+    }
+
+    /// Stable, machine-readable error code.
+    pub fn code(&self) -> &'static str {
+        match self {
+            HealthError::NotFound { .. } => "NOT_FOUND",
+            HealthError::AlreadyExists { .. } => "ALREADY_EXISTS",
+            HealthError::Expired { .. } => "EXPIRED",
+            HealthError::InvalidInput(_) => "INVALID_INPUT",
+            HealthError::InvalidState(_) => "INVALID_STATE",
+            HealthError::Unauthorized { .. } => "UNAUTHORIZED",
+            HealthError::MissingRole { .. } => "MISSING_ROLE",
+            HealthError::UnregisteredKey { .. } => "UNREGISTERED_KEY",
+            HealthError::InvalidKey { .. } => "INVALID_KEY",
+            HealthError::ConsentMissing { .. } => "CONSENT_MISSING",
+            HealthError::NotEligible { .. } => "NOT_ELIGIBLE",
+            HealthError::Storage(_) => "STORAGE_ERROR",
+        }
+    }
+}
+
+impl From<StoreError> for HealthError {
+    fn from(err: StoreError) -> Self {
+        HealthError::Storage(err.to_string())
+    }
+}
+
+impl Serialize for HealthError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("HealthError", 2)?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.end()
+    }
+}
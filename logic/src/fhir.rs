@@ -0,0 +1,452 @@
+//! HL7 FHIR R4 import and export.
+//!
+//! Imported resources are validated, stored as their JSON under the matching
+//! record type and indexed through the coded entries extracted from them, so
+//! they can be searched like any other structured record. Anonymizing one
+//! also strips the FHIR-specific identifiers (ids, narrative, references and
+//! their displays); see `anonymize`.
+//! On export, stored resources are returned as they were imported; other
+//! records are rebuilt from their structured entries, or wrapped in a
+//! DocumentReference when all we hold is an opaque payload.
+
+use serde_json::{json, Map, Value};
+
+use crate::clinical::{self, ClinicalEntry, Coding, ObservationValue, RecordType};
+use crate::error::HealthError;
+use crate::time;
+use crate::HealthRecord;
+
+/// Resource types accepted by `import`.
+const IMPORTABLE: &[&str] = &["Patient", "Observation", "Condition", "MedicationStatement", "DiagnosticReport"];
+
+/// Most resources accepted from one Bundle.
+const MAX_BUNDLE_ENTRIES: usize = 100;
+
+const BUNDLE_TYPES: &[&str] = &["collection", "transaction", "batch", "document", "searchset"];
+const OBSERVATION_STATUSES: &[&str] = &[
+    "registered", "preliminary", "final", "amended", "corrected", "cancelled", "entered-in-error", "unknown",
+];
+const MEDICATION_STATUSES: &[&str] = &[
+    "active", "completed", "entered-in-error", "intended", "stopped", "on-hold", "unknown", "not-taken",
+];
+const REPORT_STATUSES: &[&str] = &[
+    "registered", "partial", "preliminary", "final", "amended", "corrected", "appended", "cancelled",
+    "entered-in-error", "unknown",
+];
+const GENDERS: &[&str] = &["male", "female", "other", "unknown"];
+
+const CONDITION_CLINICAL_SYSTEM: &str = "http://terminology.hl7.org/CodeSystem/condition-clinical";
+
+/// A validated resource, ready to be stored as a record.
+pub struct ImportedResource {
+    pub record_type: RecordType,
+    pub data: Vec<u8>,
+    pub structured: Vec<ClinicalEntry>,
+}
+
+/// Validates a single resource or a Bundle of them and maps each onto a
+/// record. Fails on the first invalid resource, so a Bundle is all or nothing.
+pub fn import(value: &Value) -> Result<Vec<ImportedResource>, HealthError> {
+    if resource_type(value)? != "Bundle" {
+        return Ok(vec![import_resource(value)?]);
+    }
+
+    bundle_resources(value)?
+        .into_iter()
+        .enumerate()
+        .map(|(index, resource)| {
+            import_resource(resource).map_err(|err| match err {
+                HealthError::InvalidInput(message) => {
+                    HealthError::invalid_input(format!("Bundle entry {}: {}", index, message))
+                }
+                other => other,
+            })
+        })
+        .collect()
+}
+
+/// FHIR resources for one record, referencing the patient as `patient_id`.
+pub fn record_resources(record: &HealthRecord, patient_id: &str) -> Vec<Value> {
+    let subject = json!({ "reference": format!("Patient/{}", patient_id) });
+    let meta = json!({
+        "versionId": record.version.to_string(),
+        "lastUpdated": time::to_rfc3339(record.timestamp)
+    });
+
+    if let Some(mut resource) = stored_resource(&record.data) {
+        if resource.get("resourceType") == Some(&json!("Patient")) {
+            resource.insert("id".to_string(), json!(patient_id));
+        } else {
+            resource.insert("id".to_string(), json!(record.record_id));
+            resource.insert("subject".to_string(), subject);
+        }
+        resource.insert("meta".to_string(), meta);
+        return vec![Value::Object(resource)];
+    }
+
+    if record.structured.is_empty() {
+        return vec![json!({
+            "resourceType": "DocumentReference",
+            "id": record.record_id,
+            "meta": meta,
+            "status": "current",
+            "type": { "text": record.record_type.as_str() },
+            "subject": subject,
+            "date": time::to_rfc3339(record.timestamp),
+            "content": [{
+                "attachment": {
+                    "contentType": "application/octet-stream",
+                    "data": base64::encode(&record.data)
+                }
+            }]
+        })];
+    }
+
+    record.structured
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            let mut resource = entry_resource(entry, &subject);
+            resource["id"] = json!(format!("{}-{}", record.record_id, index));
+            resource["meta"] = meta.clone();
+            resource
+        })
+        .collect()
+}
+
+/// Collection Bundle of `resources`.
+pub fn bundle(resources: Vec<Value>, now: u64) -> Value {
+    json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "timestamp": time::to_rfc3339(now),
+        "entry": resources.into_iter().map(|resource| json!({ "resource": resource })).collect::<Vec<_>>()
+    })
+}
+
+fn resource_type(value: &Value) -> Result<&str, HealthError> {
+    value
+        .get("resourceType")
+        .and_then(Value::as_str)
+        .ok_or_else(|| HealthError::invalid_input("not a FHIR resource: resourceType is missing"))
+}
+
+fn bundle_resources(bundle: &Value) -> Result<Vec<&Value>, HealthError> {
+    let kind = bundle.get("type").and_then(Value::as_str).unwrap_or_default();
+    if !BUNDLE_TYPES.contains(&kind) {
+        return Err(HealthError::invalid_input(format!("unsupported Bundle type {:?}", kind)));
+    }
+
+    let entries = bundle.get("entry").and_then(Value::as_array).map(Vec::as_slice).unwrap_or_default();
+    if entries.is_empty() {
+        return Err(HealthError::invalid_input("Bundle has no entries"));
+    }
+    if entries.len() > MAX_BUNDLE_ENTRIES {
+        return Err(HealthError::invalid_input(format!(
+            "Bundle has {} entries; at most {} can be imported at once", entries.len(), MAX_BUNDLE_ENTRIES
+        )));
+    }
+
+    entries
+        .iter()
+        .enumerate()
+        .map(|(index, entry)| {
+            entry
+                .get("resource")
+                .ok_or_else(|| HealthError::invalid_input(format!("Bundle entry {} has no resource", index)))
+        })
+        .collect()
+}
+
+fn import_resource(resource: &Value) -> Result<ImportedResource, HealthError> {
+    let kind = resource_type(resource)?;
+    if let Some(id) = resource.get("id") {
+        let valid = id.as_str().is_some_and(|id| {
+            (1..=64).contains(&id.len()) && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+        });
+        if !valid {
+            return Err(HealthError::invalid_input(format!("{}.id is not a valid FHIR id", kind)));
+        }
+    }
+
+    let (record_type, structured) = match kind {
+        "Patient" => (RecordType::Demographics, patient(resource)?),
+        "Observation" => (RecordType::Observation, observation(resource)?),
+        "Condition" => (RecordType::Condition, condition(resource)?),
+        "MedicationStatement" => (RecordType::Medication, medication_statement(resource)?),
+        "DiagnosticReport" => (RecordType::DiagnosticReport, diagnostic_report(resource)?),
+        "Bundle" => return Err(HealthError::invalid_input("nested Bundles are not supported")),
+        other => {
+            return Err(HealthError::invalid_input(format!(
+                "unsupported resource type {}; expected one of {}", other, IMPORTABLE.join(", ")
+            )))
+        }
+    };
+    clinical::validate_entries(&record_type, &structured)?;
+
+    let data = serde_json::to_vec(resource).map_err(|err| HealthError::invalid_input(err.to_string()))?;
+    Ok(ImportedResource { record_type, data, structured })
+}
+
+fn patient(resource: &Value) -> Result<Vec<ClinicalEntry>, HealthError> {
+    if let Some(gender) = resource.get("gender") {
+        if !gender.as_str().is_some_and(|g| GENDERS.contains(&g)) {
+            return Err(HealthError::invalid_input(format!("Patient.gender must be one of {}", GENDERS.join(", "))));
+        }
+    }
+    if let Some(birth_date) = resource.get("birthDate") {
+        if !birth_date.as_str().is_some_and(is_fhir_date) {
+            return Err(HealthError::invalid_input("Patient.birthDate must be a FHIR date"));
+        }
+    }
+    // Demographics are kept as the resource only
+    Ok(Vec::new())
+}
+
+fn observation(resource: &Value) -> Result<Vec<ClinicalEntry>, HealthError> {
+    require_code(resource, "status", OBSERVATION_STATUSES)?;
+    let Some(code) = first_coding(require(resource, "code")?) else {
+        return Ok(Vec::new());
+    };
+
+    let value = if let Some(quantity) = resource.get("valueQuantity") {
+        let value = quantity
+            .get("value")
+            .and_then(Value::as_f64)
+            .ok_or_else(|| HealthError::invalid_input("Observation.valueQuantity needs a numeric value"))?;
+        // FHIR allows a quantity without a unit, but structured entries need
+        // one; such values stay in the stored resource only
+        quantity
+            .get("unit")
+            .or_else(|| quantity.get("code"))
+            .and_then(Value::as_str)
+            .filter(|unit| !unit.trim().is_empty())
+            .map(|unit| ObservationValue::Quantity { value, unit: unit.to_string() })
+    } else if let Some(concept) = resource.get("valueCodeableConcept") {
+        first_coding(concept).map(ObservationValue::Coded)
+    } else if let Some(text) = resource.get("valueString").and_then(Value::as_str) {
+        Some(ObservationValue::Text(text.to_string()))
+    } else {
+        resource.get("valueBoolean").and_then(Value::as_bool).map(ObservationValue::Boolean)
+    };
+
+    let effective = date(resource, "effectiveDateTime")
+        .or_else(|| date(resource, "effectiveInstant"))
+        .or_else(|| period_date(resource, "effectivePeriod", "start"));
+
+    Ok(vec![ClinicalEntry::Observation { code, value, effective }])
+}
+
+fn condition(resource: &Value) -> Result<Vec<ClinicalEntry>, HealthError> {
+    require(resource, "subject")?;
+    let Some(code) = resource.get("code").and_then(first_coding) else {
+        return Ok(Vec::new());
+    };
+
+    let clinical_status = resource
+        .pointer("/clinicalStatus/coding/0/code")
+        .and_then(Value::as_str)
+        .map(str::to_string);
+    let onset = date(resource, "onsetDateTime").or_else(|| period_date(resource, "onsetPeriod", "start"));
+    let abatement = date(resource, "abatementDateTime").or_else(|| period_date(resource, "abatementPeriod", "end"));
+
+    Ok(vec![ClinicalEntry::Condition { code, clinical_status, onset, abatement }])
+}
+
+fn medication_statement(resource: &Value) -> Result<Vec<ClinicalEntry>, HealthError> {
+    require_code(resource, "status", MEDICATION_STATUSES)?;
+    require(resource, "subject")?;
+    let concept = resource.get("medicationCodeableConcept");
+    if concept.is_none() && resource.get("medicationReference").is_none() {
+        return Err(HealthError::invalid_input("MedicationStatement.medication[x] is required"));
+    }
+    let Some(code) = concept.and_then(first_coding) else {
+        return Ok(Vec::new());
+    };
+
+    let dosage = resource.pointer("/dosage/0/text").and_then(Value::as_str).map(str::to_string);
+    let start = date(resource, "effectiveDateTime").or_else(|| period_date(resource, "effectivePeriod", "start"));
+    let end = period_date(resource, "effectivePeriod", "end");
+
+    Ok(vec![ClinicalEntry::Medication { code, dosage, start, end }])
+}
+
+fn diagnostic_report(resource: &Value) -> Result<Vec<ClinicalEntry>, HealthError> {
+    require_code(resource, "status", REPORT_STATUSES)?;
+    require(resource, "code")?;
+    // Reports have no entry kind of their own; their results are separate Observations
+    Ok(Vec::new())
+}
+
+fn entry_resource(entry: &ClinicalEntry, subject: &Value) -> Value {
+    match entry {
+        ClinicalEntry::Observation { code, value, effective } => {
+            let mut resource = json!({
+                "resourceType": "Observation",
+                "status": "final",
+                "code": concept(code),
+                "subject": subject
+            });
+            if let Some(effective) = effective {
+                resource["effectiveDateTime"] = json!(effective);
+            }
+            match value {
+                Some(ObservationValue::Quantity { value, unit }) => {
+                    resource["valueQuantity"] = json!({ "value": value, "unit": unit });
+                }
+                Some(ObservationValue::Coded(coding)) => resource["valueCodeableConcept"] = concept(coding),
+                Some(ObservationValue::Text(text)) => resource["valueString"] = json!(text),
+                Some(ObservationValue::Boolean(flag)) => resource["valueBoolean"] = json!(flag),
+                None => {}
+            }
+            resource
+        }
+        ClinicalEntry::Condition { code, clinical_status, onset, abatement } => {
+            let mut resource = json!({
+                "resourceType": "Condition",
+                "code": concept(code),
+                "subject": subject
+            });
+            if let Some(status) = clinical_status {
+                resource["clinicalStatus"] = json!({
+                    "coding": [{ "system": CONDITION_CLINICAL_SYSTEM, "code": status }]
+                });
+            }
+            if let Some(onset) = onset {
+                resource["onsetDateTime"] = json!(onset);
+            }
+            if let Some(abatement) = abatement {
+                resource["abatementDateTime"] = json!(abatement);
+            }
+            resource
+        }
+        ClinicalEntry::Medication { code, dosage, start, end } => {
+            let mut resource = json!({
+                "resourceType": "MedicationStatement",
+                "status": if end.is_some() { "completed" } else { "active" },
+                "medicationCodeableConcept": concept(code),
+                "subject": subject
+            });
+            if let Some(period) = period(start.as_deref(), end.as_deref()) {
+                resource["effectivePeriod"] = period;
+            }
+            if let Some(dosage) = dosage {
+                resource["dosage"] = json!([{ "text": dosage }]);
+            }
+            resource
+        }
+        ClinicalEntry::Allergy { substance, reaction, severity } => {
+            let mut resource = json!({
+                "resourceType": "AllergyIntolerance",
+                "code": concept(substance),
+                "patient": subject
+            });
+            // FHIR only records severity as part of a reaction
+            if let Some(reaction) = reaction {
+                let mut event = json!({ "manifestation": [concept(reaction)] });
+                if let Some(severity) = severity {
+                    event["severity"] = json!(severity);
+                }
+                resource["reaction"] = json!([event]);
+            }
+            resource
+        }
+        ClinicalEntry::Encounter { class, reason, start, end } => {
+            let mut resource = json!({
+                "resourceType": "Encounter",
+                "status": if end.is_some() { "finished" } else { "in-progress" },
+                "class": class,
+                "subject": subject,
+                "period": period(Some(start.as_str()), end.as_deref())
+            });
+            if let Some(reason) = reason {
+                resource["reasonCode"] = json!([concept(reason)]);
+            }
+            resource
+        }
+    }
+}
+
+/// A record payload that is one of the resources we import, if it is one.
+fn stored_resource(data: &[u8]) -> Option<Map<String, Value>> {
+    match serde_json::from_slice(data).ok()? {
+        Value::Object(resource) => resource
+            .get("resourceType")
+            .and_then(Value::as_str)
+            .is_some_and(|kind| IMPORTABLE.contains(&kind))
+            .then_some(resource),
+        _ => None,
+    }
+}
+
+fn require<'a>(resource: &'a Value, field: &str) -> Result<&'a Value, HealthError> {
+    resource.get(field).filter(|value| !value.is_null()).ok_or_else(|| {
+        HealthError::invalid_input(format!("{}.{} is required", resource_type(resource).unwrap_or("resource"), field))
+    })
+}
+
+fn require_code(resource: &Value, field: &str, allowed: &[&str]) -> Result<(), HealthError> {
+    if require(resource, field)?.as_str().is_some_and(|code| allowed.contains(&code)) {
+        return Ok(());
+    }
+    Err(HealthError::invalid_input(format!(
+        "{}.{} must be one of {}", resource_type(resource).unwrap_or("resource"), field, allowed.join(", ")
+    )))
+}
+
+/// First coding of a CodeableConcept that has both a system and a code.
+fn first_coding(concept: &Value) -> Option<Coding> {
+    concept
+        .get("coding")?
+        .as_array()?
+        .iter()
+        .filter(|coding| {
+            ["system", "code"]
+                .iter()
+                .all(|key| coding.get(key).and_then(Value::as_str).is_some_and(|s| !s.trim().is_empty()))
+        })
+        .find_map(|coding| serde_json::from_value(coding.clone()).ok())
+}
+
+fn concept(coding: &Coding) -> Value {
+    json!({ "coding": [coding] })
+}
+
+/// A full date from `field`. Partial FHIR dates such as `2020-05` are valid
+/// but too coarse for an entry; they stay in the stored resource only.
+fn date(resource: &Value, field: &str) -> Option<String> {
+    resource
+        .get(field)
+        .and_then(Value::as_str)
+        .filter(|value| clinical::date_part(value).is_some())
+        .map(str::to_string)
+}
+
+fn period_date(resource: &Value, field: &str, bound: &str) -> Option<String> {
+    resource.get(field).and_then(|period| date(period, bound))
+}
+
+fn period(start: Option<&str>, end: Option<&str>) -> Option<Value> {
+    let mut period = Map::new();
+    if let Some(start) = start {
+        period.insert("start".to_string(), json!(start));
+    }
+    if let Some(end) = end {
+        period.insert("end".to_string(), json!(end));
+    }
+    (!period.is_empty()).then_some(Value::Object(period))
+}
+
+/// `YYYY`, `YYYY-MM` or `YYYY-MM-DD`.
+fn is_fhir_date(value: &str) -> bool {
+    if !value.is_ascii() {
+        return false;
+    }
+    let digits = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_digit());
+    match value.len() {
+        4 => digits(value),
+        7 => digits(&value[..4]) && &value[4..5] == "-" && value[5..].parse::<u32>().is_ok_and(|m| (1..=12).contains(&m)),
+        10 => clinical::date_part(value) == Some(value),
+        _ => false,
+    }
+}
@@ -1,24 +1,201 @@
-use calimero_sdk::{app, env, types::Error};
+use std::collections::BTreeSet;
+
+use calimero_sdk::app;
+#[cfg(not(test))]
+use calimero_sdk::env;
 use calimero_sdk::borsh::{BorshDeserialize, BorshSerialize};
-use calimero_storage::collections::UnorderedMap;
+use calimero_storage::collections::{UnorderedMap, Vector};
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+mod anonymize;
+mod clinical;
+mod error;
+mod fhir;
+mod status;
+mod time;
+#[cfg(test)]
+mod tests;
+
+#[cfg(test)]
+use tests::env;
+
+use clinical::{ClinicalEntry, RecordType};
+use error::HealthError;
+use status::{PoolStatus, SubmissionStatus};
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Default, Clone)]
 pub struct HealthRecord {
+    record_id: String,     // Stable record identifier
     data: Vec<u8>,         // Encrypted patient data
     timestamp: u64,        // Record creation time
-    record_type: String,   // Type of medical record
+    record_type: RecordType, // Type of medical record
     owner_id: String,      // Patient ID
-    authorized_ids: Vec<String>,  // Authorized healthcare providers
-    is_anonymized: bool,          // Flag indicating if the data is anonymized
-    consent_proof: Option<Vec<u8>> // Proof of consent for anonymization
+    access_grants: Vec<AccessGrant>, // Time-bounded grants to healthcare providers
+    is_anonymized: bool,          // An anonymized derivative of this record exists
+    consent_proof: Option<Vec<u8>>, // Proof of consent for anonymization
+    structured: Vec<ClinicalEntry>, // Optional coded entries describing the payload
+    version: u32,          // Current version; earlier contents live in `record_versions`
+    deletion: Option<Deletion>, // Set while the record is soft-deleted and awaiting purge
+    content_hash: String   // Hex SHA-256 of `data`, computed by the context on every write
+}
+
+impl HealthRecord {
+    /// Whether `entity_id` holds any active grant, direct or through a pool.
+    fn has_active_grant(&self, entity_id: &str, now: u64) -> bool {
+        self.access_grants
+            .iter()
+            .any(|grant| grant.provider_id == entity_id && grant.expires_at > now)
+    }
+
+    /// Whether `entity_id` holds an active grant the patient gave it directly.
+    fn has_direct_grant(&self, entity_id: &str, now: u64) -> bool {
+        self.access_grants
+            .iter()
+            .any(|grant| grant.provider_id == entity_id && grant.pool_id.is_none() && grant.expires_at > now)
+    }
+
+    /// Whether `entity_id` may read the identifiable record. Pool grants only
+    /// give access to the anonymized derivative, through `get_pool_anonymized_data`.
+    fn is_visible_to(&self, entity_id: &str, now: u64) -> bool {
+        self.owner_id == entity_id || self.has_direct_grant(entity_id, now)
+    }
+
+    /// Latest expiry among every grant `entity_id` holds, direct or through a pool.
+    fn grant_expiry(&self, entity_id: &str) -> Option<u64> {
+        self.access_grants
+            .iter()
+            .filter(|grant| grant.provider_id == entity_id)
+            .map(|grant| grant.expires_at)
+            .max()
+    }
+
+    /// Why `entity_id` cannot see this record: it only holds pool grants, a
+    /// lapsed grant or none at all.
+    fn denial_reason(&self, entity_id: &str, now: u64) -> DenialReason {
+        if self.has_active_grant(entity_id, now) {
+            DenialReason::AnonymizedOnly
+        } else if self.grant_expiry(entity_id).is_some() {
+            DenialReason::GrantExpired
+        } else {
+            DenialReason::NotAuthorized
+        }
+    }
+
+    /// Adds a grant for `entity_id`, replacing the one it held through the same
+    /// pool, or directly when `pool_id` is `None`. Grants are keyed by
+    /// (provider, pool), so pools never overwrite each other or a direct grant.
+    fn upsert_grant(&mut self, entity_id: &str, now: u64, expires_at: u64, pool_id: Option<&str>) {
+        self.access_grants
+            .retain(|grant| grant.provider_id != entity_id || grant.pool_id.as_deref() != pool_id);
+        self.access_grants.push(AccessGrant {
+            provider_id: entity_id.to_string(),
+            granted_at: now,
+            expires_at,
+            pool_id: pool_id.map(str::to_string)
+        });
+    }
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Default, Clone)]
 pub struct AccessGrant {
     provider_id: String,   // Healthcare provider ID
     granted_at: u64,      // Timestamp of access grant
-    expires_at: u64       // Access expiration time
+    expires_at: u64,      // Access expiration time
+    pool_id: Option<String> // Set when the grant comes from an accepted pool submission
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct Deletion {
+    deleted_at: u64,
+    deleted_by: String,
+    purge_after: u64       // Restorable until then; purged afterwards unless under legal hold
+}
+
+/// A soft-deleted record as shown to its owner.
+#[derive(Serialize, Deserialize)]
+pub struct DeletedRecord {
+    record_id: String,
+    record_type: RecordType,
+    deleted_at: u64,
+    purge_after: u64,
+    on_legal_hold: bool
+}
+
+/// Blocks purging of a patient's deleted records, e.g. during litigation.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct LegalHold {
+    patient_id: String,
+    placed_by: String,     // Admin who placed the hold
+    reason: String,
+    placed_at: u64
+}
+
+const DEFAULT_RETENTION_SECS: u64 = 30 * time::SECS_PER_DAY;
+const MIN_RETENTION_SECS: u64 = time::SECS_PER_DAY;
+const MAX_RETENTION_SECS: u64 = 30 * 365 * time::SECS_PER_DAY;
+const MAX_PURGE_BATCH: u32 = 100;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum DenialReason {
+    NotFound,        // No matching record (also reported when the patient hides record existence)
+    ConsentMissing,  // No consent for the requested purpose
+    ConsentExpired,
+    GrantExpired,    // The entity held a grant that has lapsed
+    NotAuthorized,   // The entity never held a grant
+    NotAnonymized,   // Authorized, but the patient has not produced an anonymized derivative
+    AnonymizedOnly,  // Access comes from a research pool; read it through `get_pool_anonymized_data`
+}
+
+/// Outcome of a read: the data, or the reason access was refused.
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "status", content = "data", rename_all = "snake_case")]
+pub enum AccessResult<T> {
+    Granted(T),
+    Denied(DenialReason),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum AnonymizationMethod {
+    Derived,    // De-identified in the context from a plaintext JSON record
+    Registered, // Produced client-side and uploaded by the patient
+}
+
+/// De-identified derivative served to researchers. Carries no reference to
+/// the patient or the source record.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+pub struct AnonymizedRecord {
+    anon_id: String,
+    subject_pseudonym: String,
+    record_type: RecordType,
+    data: Vec<u8>,
+    method: AnonymizationMethod,
+    proof: Option<Vec<u8>>, // Anonymization proof supplied with a registered derivative
+    created_at: u64,
+}
+
+/// Link from a source record to its derivative, only shown to the patient.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+pub struct AnonymizationLink {
+    record_id: String,
+    anon_id: String,
+    patient_id: String,
+    created_at: u64,
+}
+
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Default, Clone)]
+pub struct PrivacySettings {
+    hide_record_existence: bool, // Report every refusal as NotFound to non-owners
+}
+
+#[derive(Serialize, Deserialize, Clone)]
+pub struct AccessGrantView {
+    record_id: String,
+    record_type: RecordType,
+    grant: AccessGrant,
+    active: bool
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Default, Clone)]
@@ -29,31 +206,429 @@ pub struct ConsentPolicy {
     expiration: u64,       // Expiration time
     proof: String     // Proof of consent
 }
+const MIN_CONSENT_DURATION_SECS: u64 = time::SECS_PER_HOUR;
+const MAX_CONSENT_DURATION_SECS: u64 = 5 * 365 * time::SECS_PER_DAY;
+
+fn normalize_purpose(purpose: &str) -> String {
+    purpose.trim().to_lowercase()
+}
+
+const MAX_IDENTITY_ID_LEN: usize = 64;
+
+/// Identity IDs are joined with `:` into consent and reward keys, so they
+/// must never contain it; only a conservative ASCII set is accepted.
+fn is_valid_identity_id(identity_id: &str) -> bool {
+    (1..=MAX_IDENTITY_ID_LEN).contains(&identity_id.len())
+        && identity_id.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '.' | '_' | '-'))
+}
+
+/// Consents are scoped per purpose: `patient_id:entity_id:purpose`.
+fn consent_key(patient_id: &str, entity_id: &str, purpose: &str) -> String {
+    format!("{}:{}:{}", patient_id, entity_id, normalize_purpose(purpose))
+}
+
+/// Consent purposes under this prefix are created by accepting a pool
+/// submission and only ever give access to anonymized derivatives.
+const POOL_PURPOSE_PREFIX: &str = "research:";
+
+/// Purpose of the consent created when a pool accepts a submission.
+fn pool_purpose(pool_id: &str) -> String {
+    normalize_purpose(&format!("{}{}", POOL_PURPOSE_PREFIX, pool_id))
+}
+
+fn is_pool_purpose(purpose: &str) -> bool {
+    normalize_purpose(purpose).starts_with(POOL_PURPOSE_PREFIX)
+}
+
+/// De-identifies a plaintext JSON record, or the structured entries of an
+/// encrypted one. `None` when the record has neither.
+fn derive_json(record: &HealthRecord, subject: &str, shift_days: i64) -> Option<Vec<u8>> {
+    anonymize::deidentify_json(&record.data, subject, shift_days).or_else(|| {
+        let entries = serde_json::to_vec(&record.structured).ok()?;
+        (!record.structured.is_empty())
+            .then(|| anonymize::deidentify_json(&entries, subject, shift_days))
+            .flatten()
+    })
+}
+
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Default, Clone)]
 pub struct ResearchPool {
+    pool_id: String,
     title: String,
     description: String,
     reward_amount: u64,
     entity_id: String,
     created_at: u64,
     expiry_date: u64,
-    status: String,
+    status: PoolStatus,
+    max_participants: Option<u32>, // Cap on open and accepted submissions
+    eligibility: EligibilityCriteria,
+}
+
+impl ResearchPool {
+    /// Drafts stay hidden from everyone but the owner.
+    fn is_visible_to(&self, identity_id: &str) -> bool {
+        self.status != PoolStatus::Draft || self.entity_id == identity_id
+    }
+}
+
+/// Rules a patient's stored records must satisfy to join a pool.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Default, Clone, Debug)]
+#[serde(default)]
+pub struct EligibilityCriteria {
+    required_record_types: Vec<RecordType>, // Each type needs at least one qualifying record
+    max_record_age_secs: Option<u64>,   // Qualifying records must be at most this old
+    require_anonymized: bool,           // Only anonymized records qualify
+}
+
+impl EligibilityCriteria {
+    fn validate(&self) -> Result<(), HealthError> {
+        for record_type in &self.required_record_types {
+            record_type.validate()?;
+        }
+        if self.max_record_age_secs == Some(0) {
+            return Err(HealthError::invalid_input("max record age must be positive"));
+        }
+        Ok(())
+    }
+
+    /// Returns why the records do not qualify, if they don't.
+    fn rejection_reason(&self, records: &[HealthRecord], now: u64) -> Option<String> {
+        let oldest = self.max_record_age_secs
+            .map(|age| now.saturating_sub(time::secs_to_nanos(age)));
+        let qualifies = |record: &HealthRecord| {
+            oldest.is_none_or(|oldest| record.timestamp >= oldest)
+                && (!self.require_anonymized || record.is_anonymized)
+        };
+
+        let describe = |what: &str| {
+            let mut reason = format!("no {}", what);
+            if self.require_anonymized {
+                reason.push_str(" anonymized");
+            }
+            reason.push_str(" record");
+            if let Some(age) = self.max_record_age_secs {
+                reason.push_str(&format!(" from the last {} days", age.div_ceil(time::SECS_PER_DAY)));
+            }
+            reason
+        };
+
+        if self.required_record_types.is_empty() {
+            if (oldest.is_some() || self.require_anonymized) && !records.iter().any(&qualifies) {
+                return Some(describe("qualifying"));
+            }
+            return None;
+        }
+
+        let missing: Vec<&str> = self.required_record_types
+            .iter()
+            .filter(|t| !records.iter().any(|r| &r.record_type == *t && qualifies(r)))
+            .map(RecordType::as_str)
+            .collect();
+
+        if missing.is_empty() {
+            None
+        } else {
+            Some(describe(&missing.join(", ")))
+        }
+    }
 }
 
 #[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
 pub struct PatientDataResponse {
+    record_id: String,
+    data: Vec<u8>,
+    record_type: RecordType,
+    timestamp: u64,
+    owner_id: String,
+    structured: Vec<ClinicalEntry>,
+    version: u32,
+    content_hash: String
+}
+
+impl From<HealthRecord> for PatientDataResponse {
+    fn from(record: HealthRecord) -> Self {
+        Self {
+            record_id: record.record_id,
+            data: record.data,
+            record_type: record.record_type,
+            timestamp: record.timestamp,
+            owner_id: record.owner_id,
+            structured: record.structured,
+            version: record.version,
+            content_hash: record.content_hash
+        }
+    }
+}
+
+/// Immutable snapshot of a record, written when it is created and on every update.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct RecordVersion {
+    record_id: String,
+    version: u32,              // Starts at 1 for the original contents
     data: Vec<u8>,
-    record_type: String,
+    record_type: RecordType,
+    structured: Vec<ClinicalEntry>,
+    author_id: String,         // Identity that wrote this version; the importer for imported ones
+    reason: Option<String>,    // Why the record was changed; None for the original
+    created_at: u64,
+    content_hash: String,      // Hex SHA-256 of `data`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    imported_from: Option<ImportedFrom>
+}
+
+/// Provenance of a version copied in from a patient bundle. Bundles are
+/// checksummed, not signed, so the author is only what the bundle claims.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct ImportedFrom {
+    source_context: String,    // Base58 ID of the context the bundle came from
+    claimed_author_id: String
+}
+
+/// History entry: a version without its payload.
+#[derive(Serialize, Deserialize)]
+pub struct RecordVersionInfo {
+    version: u32,
+    record_type: RecordType,
+    author_id: String,
+    reason: Option<String>,
+    created_at: u64,
+    imported_from: Option<ImportedFrom>
+}
+
+impl From<RecordVersion> for RecordVersionInfo {
+    fn from(version: RecordVersion) -> Self {
+        Self {
+            version: version.version,
+            record_type: version.record_type,
+            author_id: version.author_id,
+            reason: version.reason,
+            created_at: version.created_at,
+            imported_from: version.imported_from
+        }
+    }
+}
+
+/// A record version whose hash was written to the Starknet contract.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone)]
+pub struct IntegrityAnchor {
+    version: u32,
+    content_hash: String,  // Hash the patient anchored
+    tx_hash: String,       // Starknet `anchor_record_hash` transaction hash, as reported by the client
+    account: String,       // Starknet account that sent it; anchors are stored per account
+    anchored_by: String,
+    anchored_at: u64
+}
+
+/// Arguments of the contract's `get_record_anchor` for one record version,
+/// other than the anchoring account, and the value it should return.
+#[derive(Serialize, Deserialize)]
+pub struct StarknetAnchorKey {
+    context_id: String,    // `starknet_felt` of the base58 context ID
+    record_id: String,     // `starknet_felt` of the record ID
+    version: u32,
+    content_hash: String   // The payload's SHA-256 as a 0x-prefixed u256
+}
+
+/// Result of `verify_record_integrity`.
+#[derive(Serialize, Deserialize)]
+pub struct IntegrityReport {
+    record_id: String,
+    version: u32,
+    stored_hash: String,          // Hash recorded when the record was written
+    computed_hash: String,        // Hash of the payload as stored now
+    intact: bool,                 // The two match
+    tampered_versions: Vec<u32>,  // Versions in the history whose payload no longer matches its hash
+    anchor: Option<IntegrityAnchor>, // Anchor of the current version, as recorded by the patient
+    // The context cannot read Starknet: clients call `get_record_anchor` with
+    // `anchor.account` and this key, and compare the result with its hash
+    chain_key: StarknetAnchorKey
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Page<T> {
+    items: Vec<T>,
+    total: u32,
+    offset: u32,
+}
+
+const DEFAULT_PAGE_LIMIT: u32 = 20;
+const MAX_PAGE_LIMIT: u32 = 100;
+
+fn paginate<T>(items: Vec<T>, offset: Option<u32>, limit: Option<u32>) -> Page<T> {
+    let total = items.len() as u32;
+    let offset = offset.unwrap_or(0);
+    let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT).clamp(1, MAX_PAGE_LIMIT);
+
+    let items = items
+        .into_iter()
+        .skip(offset as usize)
+        .take(limit as usize)
+        .collect();
+
+    Page { items, total, offset }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum AccessOutcome {
+    Granted,
+    Denied,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+pub struct AccessLogEntry {
+    sequence: u64,              // Position in the append-only log
+    accessor_id: String,        // Identity (or raw key) of the caller
+    patient_id: String,         // Patient whose data was requested
+    record_id: Option<String>,  // Specific record, when known
+    method: String,             // Entry point that was called
+    purpose: Option<String>,    // Declared purpose of access
+    outcome: AccessOutcome,
     timestamp: u64,
-    owner_id: String
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum IdentityKind {
+    Patient,
+    Provider,
+    ResearchEntity,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+pub struct IdentityProfile {
+    identity_id: String,   // Human-readable ID used as owner_id / entity_id
+    kind: IdentityKind,
+    display_name: String,
+    keys: Vec<String>,     // Base58 executor keys bound to this identity, one per device
+    created_at: u64,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Role {
+    Patient,
+    Provider,    // Hospitals and other healthcare providers
+    Researcher,  // Research entities running pools
+    Admin,       // Context administrators managing role assignment
+    Auditor,     // Compliance staff allowed to read access logs
+    HonestBroker, // Trusted party allowed to map pool participant IDs back to patients
+}
+
+const ENTITY_ROLES: &[Role] = &[Role::Provider, Role::Researcher];
+const AUDIT_READERS: &[Role] = &[Role::Auditor, Role::Admin];
+const RECORD_READERS: &[Role] = &[Role::Patient, Role::Provider, Role::Researcher];
+
+/// Accepts an executor public key in base58 or hex and returns its base58 form.
+fn normalize_key(key: &str) -> Result<String, HealthError> {
+    let bytes = if key.len() == 64 && key.chars().all(|c| c.is_ascii_hexdigit()) {
+        hex::decode(key).ok()
+    } else {
+        bs58::decode(key).into_vec().ok()
+    };
+
+    match bytes {
+        Some(bytes) if bytes.len() == 32 => Ok(bs58::encode(bytes).into_string()),
+        _ => Err(HealthError::InvalidKey { key: key.to_string() }),
+    }
 }
 
 #[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Default, Debug)]
 pub struct PoolSubmission {
+    pool_id: String,
     patient_id: String,
     entity_id: String,
     submitted_at: u64,
-    status: String, 
+    status: SubmissionStatus,
+    record_ids: Vec<String>, // Records the patient contributes to the pool
+    history: Vec<SubmissionStatusChange>, // Every status the submission went through, oldest first
+}
+
+impl PoolSubmission {
+    fn set_status(&mut self, status: SubmissionStatus, changed_by: &str, reason: Option<String>) {
+        self.status = status;
+        self.history.push(SubmissionStatusChange {
+            status,
+            changed_at: time::now(),
+            changed_by: changed_by.to_string(),
+            reason,
+        });
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+pub struct SubmissionStatusChange {
+    status: SubmissionStatus,
+    changed_at: u64,
+    changed_by: String,     // Identity that made the change
+    reason: Option<String>, // Free-text note, e.g. why the patient withdrew
+}
+
+/// Reward budget of a pool. `funded` only grows; accepted submissions move
+/// funds into `reserved`, and claims move them from `reserved` to `paid`.
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Default, Debug)]
+pub struct PoolLedger {
+    pool_id: String,
+    funded: u64,              // Total deposited, mirrors `deposit_reward` on Starknet
+    reserved: u64,            // Owed to accepted participants, not yet claimed
+    paid: u64,                // Claimed by participants
+    accepted_count: u32,      // Submissions that ever had a reward reserved
+    deposit_refs: Vec<String>, // Starknet transaction hashes of the deposits
+}
+
+impl PoolLedger {
+    pub fn available(&self) -> u64 {
+        self.funded.saturating_sub(self.reserved).saturating_sub(self.paid)
+    }
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug)]
+#[serde(rename_all = "snake_case")]
+pub enum RewardStatus {
+    Reserved,
+    Paid,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Serialize, Deserialize, Clone, Debug)]
+pub struct RewardEntry {
+    pool_id: String,
+    patient_id: String,
+    amount: u64,                    // Fixed when reserved; later pool edits do not change it
+    status: RewardStatus,
+    reserved_at: u64,
+    paid_at: Option<u64>,
+    settlement_ref: Option<String>, // Starknet `withdraw_reward` transaction hash
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RewardBalance {
+    patient_id: String,
+    claimable: u64,
+    paid: u64,
+    rewards: Vec<RewardEntry>,
+}
+
+/// Hex SHA-256 of a record payload, as stored in `content_hash`.
+fn content_hash(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+/// Felt the Starknet contract stores an ID under: the first 31 bytes of the
+/// SHA-256 of the ID string, 0x-prefixed. 248 bits always fit below the
+/// field prime, so any record ID or base58 context ID maps to one felt.
+fn starknet_felt(id: &str) -> String {
+    format!("0x{}", hex::encode(&Sha256::digest(id.as_bytes())[..31]))
+}
+
+/// Whether `value` is a 0x-prefixed hex felt, as Starknet addresses and
+/// transaction hashes are written.
+fn is_hex_felt(value: &str) -> bool {
+    value.strip_prefix("0x").is_some_and(|digits| {
+        (1..=64).contains(&digits.len()) && digits.chars().all(|c| c.is_ascii_hexdigit())
+    })
+}
+
+/// One reward per patient per pool: `pool_id:patient_id`.
+fn reward_key(pool_id: &str, patient_id: &str) -> String {
+    format!("{}:{}", pool_id, patient_id)
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -65,29 +640,123 @@ pub struct SubmissionWithPool {
     expiry_date: u64
 }
 
+/// What `forget_patient` erased. Holds no patient ID, only the tombstone
+/// that replaced it where a reference had to remain.
+#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Clone, Debug)]
+pub struct ErasureReceipt {
+    receipt_id: String,
+    subject_tombstone: String,
+    requested_by: String,        // Admin who processed the request, or the tombstone for the patient
+    erased_at: u64,
+    records: u32,                // Removed, soft-deleted ones included
+    record_versions: u32,
+    anonymized_records: u32,
+    consents: u32,
+    pool_submissions: u32,
+    rewards_released: u32,       // Reserved rewards returned to their pools
+    rewards_tombstoned: u32,     // Paid rewards kept for bookkeeping under the tombstone
+    access_log_entries: u32,     // Audit entries rewritten to the tombstone
+    identity_keys: u32           // Executor keys unbound from the erased identity
+}
+
+const PATIENT_BUNDLE_FORMAT: &str = "medsync.patient-bundle";
+const PATIENT_BUNDLE_VERSION: u32 = 1;
+
+/// Everything stored about one patient, for download or for moving to
+/// another context. The checksum is unkeyed: it catches a corrupted bundle,
+/// but anyone who edits one can recompute it, so it proves nothing about
+/// who wrote the contents.
+#[derive(Serialize, Deserialize, Clone)]
+pub struct PatientBundle {
+    format: String,
+    format_version: u32,
+    patient_id: String,
+    source_context: String,            // Base58 ID of the context the bundle came from
+    exported_at: u64,
+    records: Vec<HealthRecord>,        // Soft-deleted records included
+    record_versions: Vec<RecordVersion>,
+    consents: Vec<ConsentPolicy>,
+    grants: Vec<AccessGrantView>,      // Expired grants included
+    pool_submissions: Vec<PoolSubmission>,
+    access_log: Vec<AccessLogEntry>,   // Entries about the patient or made by them
+    checksum: String                   // Hex SHA-256 of the JSON bundle with this field empty
+}
+
+impl PatientBundle {
+    fn compute_checksum(&self) -> Result<String, HealthError> {
+        let mut unsigned = self.clone();
+        unsigned.checksum = String::new();
+        let json = serde_json::to_vec(&unsigned).map_err(|err| HealthError::invalid_input(err.to_string()))?;
+        Ok(hex::encode(Sha256::digest(&json)))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct BundleImportSummary {
+    record_ids: Vec<String>,  // New IDs, in bundle order
+    record_versions: u32,
+    skipped_deleted: u32      // Records deleted in the source context are not imported
+}
+
 #[app::event]
 pub enum HealthEvent<'a> {
-    RecordAdded { patient_id: &'a str },
+    RecordAdded { patient_id: &'a str, record_id: &'a str },
     AccessGranted { patient_id: &'a str, provider_id: &'a str },
     RecordAccessed { patient_id: &'a str, accessor_id: &'a str },
     ConsentGranted { patient_id: &'a str, entity_id: &'a str },
-    PoolCreated { entity_id: &'a str, title: &'a str, reward_amount: u64 , expiry_date: u64 },
-    RecordDeleted { patient_id: &'a str },
-    PoolDeleted { entity_id: &'a str, title: &'a str },
-    RecordUpdated { patient_id: &'a str },
-    PoolUpdated { entity_id: &'a str, title: &'a str },
-    PoolSubmission { patient_id: &'a str, entity_id: &'a str, status: &'a str },
-    SubmissionUpdated { patient_id: &'a str, entity_id: &'a str, status: &'a str },
+    ConsentRevoked { patient_id: &'a str, entity_id: &'a str, purpose: &'a str },
+    PoolCreated { pool_id: &'a str, entity_id: &'a str, title: &'a str, reward_amount: u64 , expiry_date: u64 },
+    RecordDeleted { patient_id: &'a str, record_id: &'a str, purge_after: u64 },
+    RecordHashAnchored { patient_id: &'a str, record_id: &'a str, version: u32, tx_hash: &'a str },
+    RecordRestored { patient_id: &'a str, record_id: &'a str },
+    RecordPurged { patient_id: &'a str, record_id: &'a str },
+    LegalHoldPlaced { patient_id: &'a str, placed_by: &'a str },
+    LegalHoldReleased { patient_id: &'a str, released_by: &'a str },
+    RetentionPeriodChanged { retention_secs: u64, changed_by: &'a str },
+    PatientForgotten { receipt_id: &'a str, subject_tombstone: &'a str },
+    PoolDeleted { pool_id: &'a str, entity_id: &'a str, title: &'a str },
+    RecordUpdated { patient_id: &'a str, record_id: &'a str, version: u32 },
+    PoolUpdated { pool_id: &'a str, entity_id: &'a str, title: &'a str, status: PoolStatus },
+    PoolSubmission { pool_id: &'a str, participant_id: &'a str, entity_id: &'a str, status: SubmissionStatus },
+    SubmissionUpdated { pool_id: &'a str, participant_id: &'a str, entity_id: &'a str, status: SubmissionStatus },
+    IdentityRegistered { identity_id: &'a str, kind: IdentityKind },
+    IdentityKeyAdded { identity_id: &'a str, key: &'a str },
+    IdentityKeyRemoved { identity_id: &'a str, key: &'a str },
+    RoleAssigned { identity_id: &'a str, role: Role },
+    RoleRevoked { identity_id: &'a str, role: Role },
+    PoolFunded { pool_id: &'a str, amount: u64, funded: u64 },
+    RewardReserved { pool_id: &'a str, participant_id: &'a str, amount: u64 },
+    RewardPaid { pool_id: &'a str, participant_id: &'a str, amount: u64 },
+    SubmissionWithdrawn { pool_id: &'a str, participant_id: &'a str, entity_id: &'a str },
 }
 
 #[app::state(emits = for<'a> HealthEvent<'a>)]
 #[derive(Default, BorshSerialize, BorshDeserialize)]
 #[borsh(crate = "calimero_sdk::borsh")]
 struct HealthDataStore {
-    records: UnorderedMap<String, HealthRecord>,
+    records: UnorderedMap<String, HealthRecord>, // record_id -> record
+    record_versions: UnorderedMap<String, Vector<RecordVersion>>, // record_id -> versions, oldest first
+    patient_records: UnorderedMap<String, UnorderedMap<String, u64>>, // patient_id -> (record_id -> created_at)
+    id_nonce: u64, // Counter mixed into generated record and pool IDs
     consent_policies: UnorderedMap<String, ConsentPolicy>,
-    research_pools: UnorderedMap<String, ResearchPool>,
-    pool_submissions: UnorderedMap<String, UnorderedMap<String, PoolSubmission>>, // entity_id -> (patient_id -> submission)
+    research_pools: UnorderedMap<String, ResearchPool>, // pool_id -> pool
+    entity_pools: UnorderedMap<String, UnorderedMap<String, u64>>, // entity_id -> (pool_id -> created_at)
+    pool_submissions: UnorderedMap<String, UnorderedMap<String, PoolSubmission>>, // pool_id -> (patient_id -> submission)
+    pool_ledgers: UnorderedMap<String, PoolLedger>, // pool_id -> reward budget
+    rewards: UnorderedMap<String, RewardEntry>, // pool_id:patient_id -> reward owed or paid
+    identities: UnorderedMap<String, IdentityProfile>, // identity_id -> profile
+    identity_keys: UnorderedMap<String, String>, // base58 executor key -> identity_id
+    roles: UnorderedMap<String, Vec<Role>>, // identity_id -> assigned roles
+    access_log: Vector<AccessLogEntry>, // Append-only audit trail of data access attempts
+    privacy_settings: UnorderedMap<String, PrivacySettings>, // patient_id -> settings
+    bootstrap_admin_key: String, // Key of the context creator, made admin on registration
+    anonymized_records: UnorderedMap<String, AnonymizedRecord>, // anon_id -> derivative
+    anonymization_links: UnorderedMap<String, AnonymizationLink>, // source record_id -> derivative link
+    pseudonym_key: Vec<u8>, // Secret mixed into pseudonyms and date shifts
+    retention_secs: u64, // How long deleted records stay restorable before purge
+    legal_holds: UnorderedMap<String, LegalHold>, // patient_id -> hold blocking purge
+    erasure_receipts: UnorderedMap<String, ErasureReceipt>, // receipt_id -> receipt
+    integrity_anchors: UnorderedMap<String, Vec<IntegrityAnchor>>, // record_id -> anchored versions
 }
 
 #[allow(dead_code)]
@@ -97,492 +766,2695 @@ impl HealthDataStore {
     pub fn init() -> Self {
         Self {
             records: UnorderedMap::new(),
+            record_versions: UnorderedMap::new(),
+            patient_records: UnorderedMap::new(),
+            id_nonce: 0,
             consent_policies: UnorderedMap::new(),
             research_pools: UnorderedMap::new(),
+            entity_pools: UnorderedMap::new(),
             pool_submissions: UnorderedMap::new(),
+            pool_ledgers: UnorderedMap::new(),
+            rewards: UnorderedMap::new(),
+            identities: UnorderedMap::new(),
+            identity_keys: UnorderedMap::new(),
+            roles: UnorderedMap::new(),
+            access_log: Vector::new(),
+            privacy_settings: UnorderedMap::new(),
+            bootstrap_admin_key: bs58::encode(env::executor_id()).into_string(),
+            anonymized_records: UnorderedMap::new(),
+            anonymization_links: UnorderedMap::new(),
+            pseudonym_key: {
+                let mut key = vec![0; 32];
+                env::random_bytes(&mut key);
+                key
+            },
+            retention_secs: DEFAULT_RETENTION_SECS,
+            legal_holds: UnorderedMap::new(),
+            erasure_receipts: UnorderedMap::new(),
+            integrity_anchors: UnorderedMap::new(),
         }
     }
 
-    // Patient Data Management
-    pub fn store_patient_data(
+    // Identity Management
+    pub fn register_identity(
         &mut self,
-        patient_id: String, 
-        encrypted_data: Vec<u8>,
-        // data_hash: String,
-        record_type: String
-    ) -> Result<(), Error> {
-        env::log(&format!("Storing data for patient: {}", patient_id));
+        identity_id: String,
+        kind: IdentityKind,
+        display_name: String
+    ) -> Result<IdentityProfile, HealthError> {
+        let key = self.executor_key();
+        env::log(&format!("Registering identity {} for key {}", identity_id, key));
 
-        let record = HealthRecord {
-            data: encrypted_data,                    // Store encrypted patient data
-            timestamp: env::time_now(),    // Current timestamp
-            record_type: record_type.to_string(),    // Type of medical record
-            owner_id: patient_id.clone(),            // Patient identifier
-            authorized_ids: Vec::new(),              // Initially empty access list
-            is_anonymized: false,                    // Initially not anonymized
-            consent_proof: None                      // Initially no consent proof
+        if !is_valid_identity_id(&identity_id) {
+            return Err(HealthError::invalid_input(format!(
+                "Identity ID must be 1 to {} ASCII letters, digits, '.', '_' or '-'", MAX_IDENTITY_ID_LEN
+            )));
+        }
+        if let Some(bound) = self.identity_keys.get(&key)? {
+            return Err(HealthError::already_exists("identity key", format!("{} (bound to {})", key, bound)));
+        }
+        if self.identities.get(&identity_id)?.is_some() {
+            return Err(HealthError::already_exists("identity", identity_id));
+        }
+
+        let profile = IdentityProfile {
+            identity_id: identity_id.clone(),
+            kind,
+            display_name,
+            keys: vec![key.clone()],
+            created_at: time::now(),
         };
 
-        self.records.insert(patient_id.clone(), record)?;
-        app::emit!(HealthEvent::RecordAdded { patient_id: &patient_id });
-        Ok(())
+        self.identities.insert(identity_id.clone(), profile.clone())?;
+        self.identity_keys.insert(key.clone(), identity_id.clone())?;
+
+        app::emit!(HealthEvent::IdentityRegistered { identity_id: &identity_id, kind });
+
+        // Patients self-register; provider and researcher roles are granted by an admin
+        if kind == IdentityKind::Patient {
+            self.add_role(&identity_id, Role::Patient)?;
+        }
+        if key == self.bootstrap_admin_key && !self.any_admin()? {
+            self.add_role(&identity_id, Role::Admin)?;
+        }
+
+        Ok(profile)
     }
 
-    // Consent Management 
-    pub fn add_consent(
-        &mut self,
-        patient_id: String,
-        entity_id: String,
-        purpose: String,
-        starknet_proof: String
-    ) -> Result<(), Error> {
-        env::log(&format!("Adding consent for patient {} to entity {}", patient_id, entity_id));
+    pub fn whoami(&self) -> Result<Option<IdentityProfile>, HealthError> {
+        match self.identity_keys.get(&self.executor_key())? {
+            Some(identity_id) => Ok(self.identities.get(&identity_id)?),
+            None => Ok(None),
+        }
+    }
 
-        let consent = ConsentPolicy {
-            patient_id: patient_id.clone(),
-            entity_id: entity_id.clone(),
-            purpose,
-            expiration: env::time_now() + 7_776_000, // 90 days
-            proof: starknet_proof
-        };
+    pub fn get_identity(&self, identity_id: &str) -> Result<Option<IdentityProfile>, HealthError> {
+        self.identities.get(identity_id).map_err(HealthError::from)
+    }
+
+    // Binds an additional device key to the caller's identity
+    pub fn add_identity_key(&mut self, key: String) -> Result<(), HealthError> {
+        let identity_id = self.caller_id()?;
+        let key = normalize_key(&key)?;
 
-        // Update record authorization
-        if let Some(mut record) = self.records.get(&patient_id)? {
-            record.authorized_ids.push(entity_id.clone());
-            self.records.insert(patient_id.clone(), record)?;
+        if let Some(bound) = self.identity_keys.get(&key)? {
+            return Err(HealthError::already_exists("identity key", format!("{} (bound to {})", key, bound)));
         }
 
-        self.consent_policies.insert(format!("{}:{}", patient_id, entity_id), consent)?;
+        let mut profile = self.identities.get(&identity_id)?
+            .ok_or_else(|| HealthError::not_found("identity", &identity_id))?;
+
+        profile.keys.push(key.clone());
+        self.identities.insert(identity_id.clone(), profile)?;
+        self.identity_keys.insert(key.clone(), identity_id.clone())?;
+
+        app::emit!(HealthEvent::IdentityKeyAdded { identity_id: &identity_id, key: &key });
         Ok(())
     }
 
-    // Data Access for Hospitals
-    pub fn access_patient_data(
-        &self,
-        patient_id: &str,
-        entity_id: &str
-    ) -> Result<Option<Vec<u8>>, Error> {
-        env::log(&format!("Accessing data for patient: {} by entity: {}", patient_id, entity_id));
+    pub fn remove_identity_key(&mut self, key: String) -> Result<(), HealthError> {
+        let identity_id = self.caller_id()?;
+        let key = normalize_key(&key)?;
 
-        // Check consent
-        let consent_key = format!("{}:{}", patient_id, entity_id);
-        if let Some(consent) = self.consent_policies.get(&consent_key)? {
-            if consent.expiration < env::time_now() {
-                return Ok(None);
-            }
+        let mut profile = self.identities.get(&identity_id)?
+            .ok_or_else(|| HealthError::not_found("identity", &identity_id))?;
 
-            if let Some(record) = self.records.get(patient_id)? {
-                if record.authorized_ids.contains(&entity_id.to_string()) {
-                    app::emit!(HealthEvent::RecordAccessed { 
-                        patient_id,
-                        accessor_id: entity_id 
-                    });
-                    return Ok(Some(record.data));
-                }
-            }
+        if !profile.keys.contains(&key) {
+            return Err(HealthError::not_found("identity key", key));
         }
-        
-        Ok(None)
+        if profile.keys.len() == 1 {
+            return Err(HealthError::invalid_state("cannot remove the last key of an identity"));
+        }
+
+        profile.keys.retain(|k| k != &key);
+        self.identities.insert(identity_id.clone(), profile)?;
+        self.identity_keys.remove(&key)?;
+
+        app::emit!(HealthEvent::IdentityKeyRemoved { identity_id: &identity_id, key: &key });
+        Ok(())
     }
 
-    // Anonymized Data Access
-    pub fn get_anonymized_data(
-        &mut self,
-        patient_id: &str,
-        entity_id: &str,
-        anonymization_proof: Vec<u8>
-    ) -> Result<Option<Vec<u8>>, Error> {
-        env::log(&format!("Accessing anonymized data for research"));
-
-        if let Some(mut record) = self.records.get(patient_id)? {
-            // Verify research consent and anonymization proof
-            if record.authorized_ids.contains(&entity_id.to_string()) {
-                record.is_anonymized = true;
-                // Store proof that data was properly anonymized
-                record.consent_proof = Some(anonymization_proof);
-                self.records.insert(patient_id.to_string(), record.clone())?;
-                
-                return Ok(Some(record.data));
-            }
+    // Replaces the calling device key with `new_key`
+    pub fn rotate_identity_key(&mut self, new_key: String) -> Result<(), HealthError> {
+        let old_key = self.executor_key();
+        self.add_identity_key(new_key)?;
+        self.remove_identity_key(old_key)
+    }
+
+    // Role Management
+    pub fn assign_role(&mut self, identity_id: String, role: Role) -> Result<(), HealthError> {
+        let caller = self.caller_id()?;
+        self.authorize(&caller, &[Role::Admin])?;
+
+        if self.identities.get(&identity_id)?.is_none() {
+            return Err(HealthError::not_found("identity", identity_id));
         }
-        
-        Ok(None)
+
+        self.add_role(&identity_id, role)?;
+        Ok(())
     }
 
-    // Revoke Access
-    pub fn revoke_access(
-        &mut self,
-        patient_id: &str,
-        entity_id: &str
-    ) -> Result<(), Error> {
-        if let Some(mut record) = self.records.get(patient_id)? {
-            // Get the executor ID (caller) as bytes and compare
-            let caller = env::executor_id();
-            if record.owner_id != String::from_utf8_lossy(&caller).to_string() {
-                return Err(Error::msg("Not authorized"));
+    pub fn revoke_role(&mut self, identity_id: String, role: Role) -> Result<(), HealthError> {
+        let caller = self.caller_id()?;
+        self.authorize(&caller, &[Role::Admin])?;
+
+        let mut roles = self.roles.get(&identity_id)?.unwrap_or_default();
+        if !roles.contains(&role) {
+            return Err(HealthError::not_found("role assignment", format!("{:?} for {}", role, identity_id)));
+        }
+        if role == Role::Admin {
+            let other_admin = self.roles.entries()?
+                .any(|(id, held)| id != identity_id && held.contains(&Role::Admin));
+            if !other_admin {
+                return Err(HealthError::invalid_state("cannot revoke the last admin"));
             }
-    
-            record.authorized_ids.retain(|id| id != entity_id);
-            self.records.insert(patient_id.to_string(), record)?;
-            self.consent_policies.remove(&format!("{}:{}", patient_id, entity_id))?;
         }
-        
+
+        roles.retain(|r| *r != role);
+        self.roles.insert(identity_id.clone(), roles)?;
+
+        app::emit!(HealthEvent::RoleRevoked { identity_id: &identity_id, role });
         Ok(())
     }
 
-    pub fn get_patient_data(
-        &self,
-        patient_id: &str,
-        entity_id: &str
-    ) -> Result<Option<PatientDataResponse>, Error> {
-        env::log(&format!("Attempting to access data for patient: {}", patient_id));
-        
-        if let Some(record) = self.records.get(patient_id)? {
-            if record.owner_id == entity_id || record.authorized_ids.contains(&entity_id.to_string()) {
-                env::log(&format!("Access granted for entity: {} to patient data: {}", entity_id, patient_id));
-                env::log(&format!("Record type: {}", record.record_type));
-                env::log(&format!("Timestamp: {}", record.timestamp));
-                
-                app::emit!(HealthEvent::RecordAccessed{ 
-                    patient_id,
-                    accessor_id: entity_id 
-                });
-    
-                return Ok(Some(PatientDataResponse {
-                    data: record.data,
-                    record_type: record.record_type,
-                    timestamp: record.timestamp,
-                    owner_id: record.owner_id
-                }));
-            }
-            env::log(&format!("Access denied for entity: {} to patient data: {}", entity_id, patient_id));
+    pub fn get_my_roles(&self) -> Result<Vec<Role>, HealthError> {
+        let caller = self.caller_id()?;
+        Ok(self.roles.get(&caller)?.unwrap_or_default())
+    }
+
+    pub fn get_roles(&self, identity_id: &str) -> Result<Vec<Role>, HealthError> {
+        let caller = self.caller_id()?;
+        if caller != identity_id {
+            self.authorize(&caller, &[Role::Admin])?;
+        }
+        Ok(self.roles.get(identity_id)?.unwrap_or_default())
+    }
+
+    // Access Audit Log
+    pub fn get_access_log(
+        &self,
+        patient_id: &str,
+        offset: Option<u32>,
+        limit: Option<u32>
+    ) -> Result<Page<AccessLogEntry>, HealthError> {
+        let caller = self.caller_id()?;
+        if caller == patient_id {
+            self.authorize(patient_id, &[Role::Patient])?;
         } else {
-            env::log(&format!("No record found for patient: {}", patient_id));
+            self.authorize(&caller, AUDIT_READERS)?;
         }
-        Ok(None)
+
+        let entries = self.access_log_newest_first(|entry| entry.patient_id == patient_id)?;
+        Ok(paginate(entries, offset, limit))
     }
 
-    pub fn grant_access(
+    // Reserved to auditors. Entries name the patient, so letting entities read
+    // their own log would tell a researcher who is behind each pool participant.
+    pub fn get_entity_access_log(
+        &self,
+        entity_id: &str,
+        offset: Option<u32>,
+        limit: Option<u32>
+    ) -> Result<Page<AccessLogEntry>, HealthError> {
+        let caller = self.caller_id()?;
+        self.authorize(&caller, AUDIT_READERS)?;
+
+        let entries = self.access_log_newest_first(|entry| entry.accessor_id == entity_id)?;
+        Ok(paginate(entries, offset, limit))
+    }
+
+    // Patient Data Management
+    pub fn store_patient_data(
         &mut self,
-        patient_id: String,
-        entity_id: String
-    ) -> Result<(), Error> {
-        if let Some(mut record) = self.records.get(&patient_id)? {
-            let caller = env::executor_id();
-            if record.owner_id != String::from_utf8_lossy(&caller).to_string() {
-                return Err(Error::msg("Not authorized"));
+        patient_id: String, 
+        encrypted_data: Vec<u8>,
+        data_hash: Option<String>, // Client-side SHA-256 of `encrypted_data`, checked on arrival
+        record_type: RecordType,
+        structured: Option<Vec<ClinicalEntry>>
+    ) -> Result<String, HealthError> {
+        env::log(&format!("Storing data for patient: {}", patient_id));
+        self.authorize(&patient_id, &[Role::Patient])?;
+
+        if let Some(expected) = data_hash {
+            if !expected.trim().eq_ignore_ascii_case(&content_hash(&encrypted_data)) {
+                return Err(HealthError::invalid_input("data_hash does not match the uploaded data"));
             }
-            
-            record.authorized_ids.push(entity_id.clone());
-            self.records.insert(patient_id.clone(), record)?;
-            
-            app::emit!(HealthEvent::ConsentGranted { 
-                patient_id: &patient_id,
-                entity_id: &entity_id 
-            });
         }
-        Ok(())
-    }
 
-    pub fn create_research_pool(
-        &mut self,
-        entity_id: String,
-        title: String,
-        description: String,
-        reward_amount: u64,
-        expiry_date: u64
-    ) -> Result<(), Error> {
-        env::log(&format!("Creating research pool: {} by entity: {}", title, entity_id));
+        let structured = structured.unwrap_or_default();
+        clinical::validate_entries(&record_type, &structured)?;
 
-        let pool = ResearchPool {
-            title: title.clone(),
-            description,
-            reward_amount,
-            entity_id: entity_id.clone(),
-            created_at: env::time_now(),
-            expiry_date,
-            status: "active".to_string(),
-        };
+        self.insert_record(&patient_id, encrypted_data, record_type, structured)
+    }
 
-        self.research_pools.insert(entity_id.clone(), pool)?;
+    // Stores FHIR R4 resources, sent alone or as a Bundle, as records of the
+    // matching type. Nothing is stored unless every resource is valid.
+    pub fn import_fhir(
+        &mut self,
+        patient_id: String,
+        resource: serde_json::Value
+    ) -> Result<Vec<String>, HealthError> {
+        env::log(&format!("Importing FHIR data for patient: {}", patient_id));
+        self.authorize(&patient_id, &[Role::Patient])?;
 
-        app::emit!(HealthEvent::PoolCreated { 
-            entity_id: &entity_id,
-            title: &title,
-            reward_amount,
-            expiry_date
-        });
+        let imported = fhir::import(&resource)?;
+        let mut record_ids = Vec::with_capacity(imported.len());
+        for resource in imported {
+            record_ids.push(self.insert_record(&patient_id, resource.data, resource.record_type, resource.structured)?);
+        }
 
-        Ok(())
+        env::log(&format!("Imported {} FHIR resources", record_ids.len()));
+        Ok(record_ids)
     }
 
-    pub fn get_research_pool(
-        &self,
+    // FHIR Bundle of the patient's records visible to `entity_id`. Records shared
+    // only through a pool are left out; they are served anonymized instead.
+    pub fn export_fhir_bundle(
+        &mut self,
+        patient_id: &str,
         entity_id: &str
-    ) -> Result<Option<ResearchPool>, Error> {
-        self.research_pools.get(entity_id).map_err(Error::from)
-    }
+    ) -> Result<serde_json::Value, HealthError> {
+        env::log(&format!("Exporting FHIR bundle for patient: {} to entity: {}", patient_id, entity_id));
+        self.authorize_audited(entity_id, RECORD_READERS, "export_fhir_bundle", patient_id, None, None)?;
 
-    pub fn list_research_pools(&self) -> Result<Vec<ResearchPool>, Error> {
-    env::log("Listing all research pools");
-    
-    let mut pools = Vec::new();
-    let current_time = env::time_now();
-    
- 
-    for (_, pool) in self.research_pools.entries()? {
-        if pool.status == "active" && pool.expiry_date > current_time {
-            pools.push(pool);
+        let now = time::now();
+        let mut resources = Vec::new();
+
+        for record in self.patient_records_newest_first(patient_id)? {
+            if !record.is_visible_to(entity_id, now) {
+                continue;
+            }
+
+            let exported = fhir::record_resources(&record, patient_id);
+            if !exported.is_empty() {
+                self.audit("export_fhir_bundle", patient_id, Some(&record.record_id), None, AccessOutcome::Granted)?;
+                resources.extend(exported);
+            }
         }
-    }
-    
 
-    pools.sort_by(|a, b| b.created_at.cmp(&a.created_at));
-    
-    env::log(&format!("Found {} active research pools", pools.len()));
-    Ok(pools)
-}
+        env::log(&format!("Exported {} FHIR resources", resources.len()));
+        Ok(fhir::bundle(resources, now))
+    }
 
-    pub fn list_authorized_reports(
-        &self,
+    pub fn get_record(
+        &mut self,
+        record_id: &str,
         entity_id: &str
-    ) -> Result<Vec<PatientDataResponse>, Error> {
-        env::log(&format!("Listing authorized reports for entity: {}", entity_id));
-        
-        let mut authorized_reports = Vec::new();
-      
-        for (_, record) in self.records.entries()? {                
-            if record.authorized_ids.contains(&entity_id.to_string()) {
-                authorized_reports.push(PatientDataResponse {
-                    data: record.data.clone(),
-                    record_type: record.record_type.clone(),
-                    timestamp: record.timestamp,
-                    owner_id: record.owner_id.clone()
-                });
+    ) -> Result<AccessResult<PatientDataResponse>, HealthError> {
+        env::log(&format!("Attempting to access record: {}", record_id));
+
+        let record = match self.readable_record(record_id, entity_id, "get_record")? {
+            Ok(record) => record,
+            Err(denied) => {
+                env::log(&format!("Access denied for entity: {} to record: {}", entity_id, record_id));
+                return Ok(denied);
             }
-        }
-        
-        env::log(&format!("Found {} authorized reports", authorized_reports.len()));
-        Ok(authorized_reports)
+        };
+
+        app::emit!(HealthEvent::RecordAccessed {
+            patient_id: &record.owner_id,
+            accessor_id: entity_id
+        });
+        Ok(AccessResult::Granted(PatientDataResponse::from(record)))
     }
 
+    // `from` and `to` are Unix seconds and both ends are inclusive
+    pub fn list_patient_records(
+        &mut self,
+        patient_id: &str,
+        entity_id: &str,
+        record_type: Option<RecordType>,
+        code: Option<String>,
+        from: Option<u64>,
+        to: Option<u64>,
+        offset: Option<u32>,
+        limit: Option<u32>
+    ) -> Result<Page<PatientDataResponse>, HealthError> {
+        env::log(&format!("Listing records for patient: {} by entity: {}", patient_id, entity_id));
+        self.authorize_audited(entity_id, RECORD_READERS, "list_patient_records", patient_id, None, None)?;
 
-    pub fn delete_patient_data(&mut self, patient_id: &str) -> Result<(), Error> {
-        env::log(&format!("Deleting data for patient: {}", patient_id));
-        
-   
-        let caller = env::executor_id();
-        
-        if let Some(record) = self.records.get(patient_id)? {
-       
-            if record.owner_id != String::from_utf8_lossy(&caller).to_string() {
-                return Err(Error::msg("Not authorized to delete this record"));
+        let mut matching = Vec::new();
+        let now = time::now();
+        let from = from.map(time::from_unix_secs);
+        let until = to.map(|to| time::from_unix_secs(to.saturating_add(1)));
+
+        for record in self.patient_records_newest_first(patient_id)? {
+            if !record.is_visible_to(entity_id, now) {
+                continue;
             }
-            
-          
-            self.records.remove(patient_id)?;
-            
-     
-            let keys_to_remove: Vec<String> = self.consent_policies.entries()?
-                .filter_map(|(key, _)| {
-                    if key.starts_with(&format!("{}:", patient_id)) {
-                        Some(key)
-                    } else {
-                        None
-                    }
-                })
-                .collect();
-            
-            for key in keys_to_remove {
-                self.consent_policies.remove(&key)?;
+            if record_type.as_ref().is_some_and(|t| &record.record_type != t) {
+                continue;
             }
-            
-            app::emit!(HealthEvent::RecordDeleted { patient_id });
-            Ok(())
-        } else {
-            Err(Error::msg("Record not found"))
+            if code.as_ref().is_some_and(|c| !record.structured.iter().flat_map(ClinicalEntry::codings).any(|coding| coding.code() == c)) {
+                continue;
+            }
+            if from.is_some_and(|f| record.timestamp < f) || until.is_some_and(|u| record.timestamp >= u) {
+                continue;
+            }
+            matching.push(PatientDataResponse::from(record));
         }
-    }
 
-    pub fn delete_research_pool(&mut self, entity_id: &str) -> Result<(), Error> {
-        env::log(&format!("Deleting research pool for entity: {}", entity_id));
-        
-        let caller = env::executor_id();
-        
-        if let Some(pool) = self.research_pools.get(entity_id)? {
-            // Verify ownership
-            if pool.entity_id != String::from_utf8_lossy(&caller).to_string() {
-                return Err(Error::msg("Not authorized to delete this pool"));
-            }
-            
-            self.research_pools.remove(entity_id)?;
-            
-            app::emit!(HealthEvent::PoolDeleted { 
-                entity_id,
-                title: &pool.title 
-            });
-            Ok(())
-        } else {
-            Err(Error::msg("Pool not found"))
+        env::log(&format!("Found {} matching records", matching.len()));
+        let page = paginate(matching, offset, limit);
+
+        for item in &page.items {
+            self.audit("list_patient_records", patient_id, Some(&item.record_id), None, AccessOutcome::Granted)?;
         }
+        Ok(page)
     }
 
+    // Every record the patient owns, newest first. Only the patient may call this.
+    pub fn get_patient_records(&self, patient_id: &str) -> Result<Vec<PatientDataResponse>, HealthError> {
+        self.authorize(patient_id, &[Role::Patient])?;
 
-    pub fn update_patient_data(
+        Ok(self.patient_records_newest_first(patient_id)?
+            .into_iter()
+            .map(PatientDataResponse::from)
+            .collect())
+    }
+
+    // Writes a new version of the record; earlier versions stay readable through
+    // `get_record_version`. Structured entries describe the payload, so they
+    // are replaced along with it.
+    pub fn update_record(
         &mut self,
-        patient_id: &str,
+        record_id: &str,
         new_data: Vec<u8>,
-        record_type: String
-    ) -> Result<(), Error> {
-        env::log(&format!("Updating data for patient: {}", patient_id));
-        
-        let caller = env::executor_id();
-        
-        if let Some(mut record) = self.records.get(patient_id)? {
+        record_type: RecordType,
+        structured: Option<Vec<ClinicalEntry>>,
+        reason: Option<String>
+    ) -> Result<(), HealthError> {
+        env::log(&format!("Updating record: {}", record_id));
+
+        if let Some(mut record) = self.live_record(record_id)? {
             // Verify ownership
-            if record.owner_id != String::from_utf8_lossy(&caller).to_string() {
-                return Err(Error::msg("Not authorized to update this record"));
-            }
-            
-            // Update record
+            self.authorize(&record.owner_id, &[Role::Patient])?;
+
+            let structured = structured.unwrap_or_default();
+            clinical::validate_entries(&record_type, &structured)?;
+
+            record.content_hash = content_hash(&new_data);
             record.data = new_data;
             record.record_type = record_type;
-            record.timestamp = env::time_now();
-            
-            self.records.insert(patient_id.to_string(), record)?;
-            
-            app::emit!(HealthEvent::RecordUpdated { patient_id });
+            record.structured = structured;
+            record.timestamp = time::now();
+            record.version += 1;
+
+            let author_id = self.caller_id()?;
+            let reason = reason.map(|r| r.trim().to_string()).filter(|r| !r.is_empty());
+            self.push_version(&record, &author_id, reason)?;
+
+            // A derivative of the old contents no longer matches the record
+            self.remove_derivative(record_id)?;
+            record.is_anonymized = false;
+
+            let patient_id = record.owner_id.clone();
+            let record_version = record.version;
+            self.records.insert(record_id.to_string(), record)?;
+
+            app::emit!(HealthEvent::RecordUpdated { patient_id: &patient_id, record_id, version: record_version });
             Ok(())
         } else {
-            Err(Error::msg("Record not found"))
+            Err(HealthError::not_found("record", record_id))
         }
     }
 
+    // Versions of a record, newest first, without their payloads
+    pub fn get_record_history(
+        &mut self,
+        record_id: &str,
+        entity_id: &str
+    ) -> Result<AccessResult<Vec<RecordVersionInfo>>, HealthError> {
+        env::log(&format!("Listing versions of record: {}", record_id));
+
+        let record = match self.readable_record(record_id, entity_id, "get_record_history")? {
+            Ok(record) => record,
+            Err(denied) => return Ok(denied),
+        };
 
-    pub fn update_research_pool(
+        let mut history: Vec<RecordVersionInfo> = self.record_versions(&record)?
+            .into_iter()
+            .map(RecordVersionInfo::from)
+            .collect();
+        history.reverse();
+        Ok(AccessResult::Granted(history))
+    }
+
+    pub fn get_record_version(
         &mut self,
+        record_id: &str,
         entity_id: &str,
-        title: Option<String>,
-        description: Option<String>,
-        reward_amount: Option<u64>,
-        status: Option<String>
-    ) -> Result<(), Error> {
-        env::log(&format!("Updating research pool for entity: {}", entity_id));
-        
-        let caller = env::executor_id();
-        
-        if let Some(mut pool) = self.research_pools.get(entity_id)? {
-            // Verify ownership
-            if pool.entity_id != String::from_utf8_lossy(&caller).to_string() {
-                return Err(Error::msg("Not authorized to update this pool"));
-            }
-            
-            // Update fields if provided
-            if let Some(t) = title { pool.title = t; }
-            if let Some(d) = description { pool.description = d; }
-            if let Some(r) = reward_amount { pool.reward_amount = r; }
-            if let Some(s) = status { pool.status = s; }
-            
-            self.research_pools.insert(entity_id.to_string(), pool.clone())?;
-            
-            app::emit!(HealthEvent::PoolUpdated { 
-                entity_id,
-                title: &pool.title 
-            });
-            Ok(())
-        } else {
-            Err(Error::msg("Pool not found"))
+        version: u32
+    ) -> Result<AccessResult<RecordVersion>, HealthError> {
+        env::log(&format!("Reading version {} of record: {}", version, record_id));
+
+        let record = match self.readable_record(record_id, entity_id, "get_record_version")? {
+            Ok(record) => record,
+            Err(denied) => return Ok(denied),
+        };
+
+        match self.record_versions(&record)?.into_iter().find(|v| v.version == version) {
+            Some(found) => Ok(AccessResult::Granted(found)),
+            None => Ok(AccessResult::Denied(DenialReason::NotFound)),
         }
     }
 
-    pub fn submit_to_pool(
+    // Recomputes the hash of the stored payload and of every earlier version
+    // and compares them with the hashes recorded on write. Checking the
+    // Starknet anchor is left to the client, with the returned `chain_key`.
+    pub fn verify_record_integrity(
         &mut self,
-        entity_id: &str,
-        patient_id: &str,
-    ) -> Result<(), Error> {
-        // Get pool and check expiry
-        let pool = self.research_pools.get(entity_id)?
-            .ok_or_else(|| Error::msg("Pool not found"))?;
-        
-        let current_time = env::time_now();
-        if current_time >= pool.expiry_date {
-            return Err(Error::msg("Pool has expired"));
+        record_id: &str,
+        entity_id: &str
+    ) -> Result<AccessResult<IntegrityReport>, HealthError> {
+        env::log(&format!("Verifying integrity of record: {}", record_id));
+
+        let record = match self.readable_record(record_id, entity_id, "verify_record_integrity")? {
+            Ok(record) => record,
+            Err(denied) => return Ok(denied),
+        };
+
+        let computed_hash = content_hash(&record.data);
+        let tampered_versions = self.record_versions(&record)?
+            .into_iter()
+            .filter(|version| version.content_hash != content_hash(&version.data))
+            .map(|version| version.version)
+            .collect();
+        let anchor = self.integrity_anchors
+            .get(record_id)?
+            .unwrap_or_default()
+            .into_iter()
+            .find(|anchor| anchor.version == record.version);
+
+        if computed_hash != record.content_hash {
+            env::log(&format!("Record {} does not match its stored hash", record_id));
         }
-    
-        let submission = PoolSubmission {
-            patient_id: patient_id.to_string(),
-            entity_id: entity_id.to_string(),
-            submitted_at: current_time,
-            status: "pending".to_string(),
+
+        Ok(AccessResult::Granted(IntegrityReport {
+            chain_key: StarknetAnchorKey {
+                context_id: starknet_felt(&bs58::encode(env::context_id()).into_string()),
+                record_id: starknet_felt(&record.record_id),
+                version: record.version,
+                content_hash: format!("0x{}", computed_hash)
+            },
+            record_id: record.record_id,
+            version: record.version,
+            intact: computed_hash == record.content_hash,
+            stored_hash: record.content_hash,
+            computed_hash,
+            tampered_versions,
+            anchor
+        }))
+    }
+
+    // Records that the patient's Starknet `account` wrote the current version's
+    // hash to the contract in `tx_hash`, under the key `verify_record_integrity`
+    // reports. The context cannot reach Starknet, so this is the patient's
+    // claim; readers check it against the chain themselves.
+    pub fn anchor_record_hash(
+        &mut self,
+        record_id: &str,
+        tx_hash: String,
+        account: String
+    ) -> Result<IntegrityAnchor, HealthError> {
+        let record = self.live_record(record_id)?
+            .ok_or_else(|| HealthError::not_found("record", record_id))?;
+        self.authorize(&record.owner_id, &[Role::Patient])?;
+
+        let tx_hash = tx_hash.trim().to_lowercase();
+        if !is_hex_felt(&tx_hash) {
+            return Err(HealthError::invalid_input("tx_hash must be a 0x-prefixed Starknet transaction hash"));
+        }
+        let account = account.trim().to_lowercase();
+        if !is_hex_felt(&account) {
+            return Err(HealthError::invalid_input("account must be a 0x-prefixed Starknet address"));
+        }
+
+        let mut anchors = self.integrity_anchors.get(record_id)?.unwrap_or_default();
+        if anchors.iter().any(|anchor| anchor.version == record.version) {
+            return Err(HealthError::already_exists("anchor", format!("{}@{}", record_id, record.version)));
+        }
+
+        let anchor = IntegrityAnchor {
+            version: record.version,
+            content_hash: record.content_hash.clone(),
+            tx_hash,
+            account,
+            anchored_by: self.caller_id()?,
+            anchored_at: time::now()
         };
-    
-        let mut entity_submissions = self.pool_submissions
-            .get(entity_id)?
-            .unwrap_or_else(|| UnorderedMap::new());
-        
-        entity_submissions.insert(patient_id.to_string(), submission)?;
-        self.pool_submissions.insert(entity_id.to_string(), entity_submissions)?;
-    
-        app::emit!(HealthEvent::PoolSubmission {
-            patient_id,
-            entity_id,
-            status: "pending"
+        anchors.push(anchor.clone());
+        self.integrity_anchors.insert(record_id.to_string(), anchors)?;
+
+        app::emit!(HealthEvent::RecordHashAnchored {
+            patient_id: &record.owner_id,
+            record_id,
+            version: anchor.version,
+            tx_hash: &anchor.tx_hash
         });
-    
-        Ok(())
+        Ok(anchor)
     }
 
-    pub fn update_submission_status(
-        &mut self,
-        entity_id: &str,
-        patient_id: &str,
-        status: String,
-    ) -> Result<(), Error> {
-      
-        let mut entity_submissions = self.pool_submissions
-            .get(entity_id)?
-            .ok_or_else(|| Error::msg("No submissions found"))?;
+    // Soft-deletes the record. It stays restorable for the retention period
+    // and is purged by `purge_deleted_records` afterwards.
+    pub fn delete_record(&mut self, record_id: &str) -> Result<(), HealthError> {
+        env::log(&format!("Deleting record: {}", record_id));
 
-      
-        if let Some(mut submission) = entity_submissions.get(patient_id)? {
-            submission.status = status.clone();
-            entity_submissions.insert(patient_id.to_string(), submission)?;
-            self.pool_submissions.insert(entity_id.to_string(), entity_submissions)?;
+        if let Some(record) = self.live_record(record_id)? {
+            self.authorize(&record.owner_id, &[Role::Patient])?;
 
-            app::emit!(HealthEvent::SubmissionUpdated {
-                patient_id,
-                entity_id,
-                status: &status
-            });
-            
-            Ok(())
+            let deleted_by = self.caller_id()?;
+            self.soft_delete(record, &deleted_by)
         } else {
-            Err(Error::msg("Submission not found"))
+            Err(HealthError::not_found("record", record_id))
         }
     }
 
-    pub fn get_patient_submissions(
-        &self,
-        patient_id: &str
-    ) -> Result<Vec<SubmissionWithPool>, Error> {
-        let mut submissions = Vec::new();
-        
-        for (entity_id, entity_submissions) in self.pool_submissions.entries()? {
-            if let Some(submission) = entity_submissions.get(patient_id)? {
-            
-                if let Some(pool) = self.research_pools.get(&entity_id)? {
-                    submissions.push(SubmissionWithPool {
-                        submission,
-                        pool_title: pool.title,
-                        pool_description: pool.description,
-                        reward_amount: pool.reward_amount,
-                        expiry_date: pool.expiry_date
+    // Undoes a deletion while the record is still within its retention period
+    pub fn restore_record(&mut self, record_id: &str) -> Result<(), HealthError> {
+        env::log(&format!("Restoring record: {}", record_id));
+
+        let mut record = self.records.get(record_id)?
+            .filter(|record| record.deletion.is_some())
+            .ok_or_else(|| HealthError::not_found("deleted record", record_id))?;
+        self.authorize(&record.owner_id, &[Role::Patient])?;
+
+        if record.deletion.as_ref().is_some_and(|deletion| deletion.purge_after <= time::now()) {
+            return Err(HealthError::expired("deleted record", record_id));
+        }
+
+        record.deletion = None;
+        let patient_id = record.owner_id.clone();
+        self.records.insert(record_id.to_string(), record)?;
+
+        app::emit!(HealthEvent::RecordRestored { patient_id: &patient_id, record_id });
+        Ok(())
+    }
+
+    // The patient's soft-deleted records that have not been purged yet
+    pub fn list_deleted_records(&self, patient_id: &str) -> Result<Vec<DeletedRecord>, HealthError> {
+        self.authorize(patient_id, &[Role::Patient])?;
+
+        let on_legal_hold = self.legal_holds.contains(patient_id)?;
+        let mut deleted = Vec::new();
+        if let Some(index) = self.patient_records.get(patient_id)? {
+            for (record_id, _) in index.entries()? {
+                let Some(record) = self.records.get(&record_id)? else {
+                    continue;
+                };
+                if let Some(deletion) = record.deletion {
+                    deleted.push(DeletedRecord {
+                        record_id,
+                        record_type: record.record_type,
+                        deleted_at: deletion.deleted_at,
+                        purge_after: deletion.purge_after,
+                        on_legal_hold
                     });
                 }
             }
         }
 
-        submissions.sort_by(|a, b| b.submission.submitted_at.cmp(&a.submission.submitted_at));
-        Ok(submissions)
-    }  
-}
\ No newline at end of file
+        deleted.sort_by(|a, b| b.deleted_at.cmp(&a.deleted_at));
+        Ok(deleted)
+    }
+
+    // Consent Management 
+    pub fn add_consent(
+        &mut self,
+        patient_id: String,
+        entity_id: String,
+        purpose: String,
+        duration_secs: u64,
+        starknet_proof: String
+    ) -> Result<(), HealthError> {
+        env::log(&format!("Adding consent for patient {} to entity {}", patient_id, entity_id));
+        self.authorize(&patient_id, &[Role::Patient])?;
+
+        if normalize_purpose(&purpose).is_empty() {
+            return Err(HealthError::invalid_input("consent purpose must not be empty"));
+        }
+        if is_pool_purpose(&purpose) {
+            return Err(HealthError::invalid_input("research purposes are consented to by submitting to the pool"));
+        }
+        if !(MIN_CONSENT_DURATION_SECS..=MAX_CONSENT_DURATION_SECS).contains(&duration_secs) {
+            return Err(HealthError::invalid_input(format!(
+                "consent duration must be between {} and {} seconds",
+                MIN_CONSENT_DURATION_SECS, MAX_CONSENT_DURATION_SECS
+            )));
+        }
+
+        let consent = ConsentPolicy {
+            patient_id: patient_id.clone(),
+            entity_id: entity_id.clone(),
+            purpose: normalize_purpose(&purpose),
+            expiration: time::expiry_after(duration_secs),
+            proof: starknet_proof
+        };
+
+        // A consent covers reads made for its purpose through
+        // `access_patient_data`; it grants nothing on the other read paths
+        self.consent_policies.insert(consent_key(&patient_id, &entity_id, &purpose), consent)?;
+
+        app::emit!(HealthEvent::ConsentGranted {
+            patient_id: &patient_id,
+            entity_id: &entity_id
+        });
+        Ok(())
+    }
+
+    // Privacy Settings
+    pub fn set_privacy_settings(
+        &mut self,
+        patient_id: &str,
+        hide_record_existence: bool
+    ) -> Result<(), HealthError> {
+        self.authorize(patient_id, &[Role::Patient])?;
+
+        self.privacy_settings.insert(patient_id.to_string(), PrivacySettings { hide_record_existence })?;
+        Ok(())
+    }
+
+    pub fn get_privacy_settings(&self, patient_id: &str) -> Result<PrivacySettings, HealthError> {
+        self.authorize(patient_id, &[Role::Patient])?;
+
+        Ok(self.privacy_settings.get(patient_id)?.unwrap_or_default())
+    }
+
+    // Lets the patient or the consented entity check a consent before accessing data
+    pub fn verify_consent(
+        &self,
+        patient_id: &str,
+        entity_id: &str,
+        purpose: &str
+    ) -> Result<ConsentPolicy, HealthError> {
+        let caller = self.caller_id()?;
+        if caller == patient_id {
+            self.authorize(patient_id, &[Role::Patient])?;
+        } else {
+            self.authorize(entity_id, ENTITY_ROLES)?;
+        }
+
+        let consent = self.consent_policies
+            .get(&consent_key(patient_id, entity_id, purpose))?
+            .ok_or_else(|| HealthError::ConsentMissing {
+                patient_id: patient_id.to_string(),
+                entity_id: entity_id.to_string(),
+                purpose: normalize_purpose(purpose),
+            })?;
+
+        if consent.expiration < time::now() {
+            return Err(HealthError::expired("consent", consent_key(patient_id, entity_id, purpose)));
+        }
+        Ok(consent)
+    }
+
+    // Data Access for Hospitals
+    // Serves the patient's latest record to an entity holding an unexpired
+    // consent for `purpose`. Pool purposes never serve the source record.
+    pub fn access_patient_data(
+        &mut self,
+        patient_id: &str,
+        entity_id: &str,
+        purpose: &str
+    ) -> Result<AccessResult<Vec<u8>>, HealthError> {
+        env::log(&format!("Accessing data for patient: {} by entity: {} for purpose: {}", patient_id, entity_id, purpose));
+        self.authorize_audited(entity_id, ENTITY_ROLES, "access_patient_data", patient_id, None, Some(purpose))?;
+
+        let records = self.patient_records_newest_first(patient_id)?;
+        let now = time::now();
+
+        // Check consent for the requested purpose
+        let reason = match self.consent_policies.get(&consent_key(patient_id, entity_id, purpose))? {
+            _ if records.is_empty() => DenialReason::NotFound,
+            _ if is_pool_purpose(purpose) => DenialReason::AnonymizedOnly,
+            None => DenialReason::ConsentMissing,
+            // The key includes the purpose, so a consent for another purpose is never found
+            Some(consent) if consent.expiration < now => DenialReason::ConsentExpired,
+            Some(_) => {
+                let record = &records[0];
+                self.audit("access_patient_data", patient_id, Some(&record.record_id), Some(purpose), AccessOutcome::Granted)?;
+                app::emit!(HealthEvent::RecordAccessed { 
+                    patient_id,
+                    accessor_id: entity_id 
+                });
+                return Ok(AccessResult::Granted(record.data.clone()));
+            }
+        };
+        
+        self.audit("access_patient_data", patient_id, None, Some(purpose), AccessOutcome::Denied)?;
+        self.deny(patient_id, reason)
+    }
+
+    // Anonymized Data Access
+    // De-identifies a plaintext JSON record, or the structured entries of an
+    // encrypted one, in the context. Encrypted records without entries must be
+    // anonymized client-side and registered instead.
+    pub fn anonymize_record(&mut self, record_id: &str) -> Result<AnonymizedRecord, HealthError> {
+        let record = self.live_record(record_id)?
+            .ok_or_else(|| HealthError::not_found("record", record_id))?;
+        self.authorize(&record.owner_id, &[Role::Patient])?;
+
+        let key = self.pseudonym_key();
+        let subject = anonymize::subject_pseudonym(&key, &record.owner_id);
+        let shift = anonymize::date_shift_days(&key, &record.owner_id);
+        let data = derive_json(&record, &subject, shift)
+            .ok_or_else(|| {
+                HealthError::invalid_input("record has neither a JSON payload nor structured entries; register a client-side derivative instead")
+            })?;
+
+        self.store_derivative(record, subject, data, AnonymizationMethod::Derived, None)
+    }
+
+    pub fn register_anonymized_record(
+        &mut self,
+        record_id: &str,
+        anonymized_data: Vec<u8>,
+        anonymization_proof: Vec<u8>
+    ) -> Result<AnonymizedRecord, HealthError> {
+        let record = self.live_record(record_id)?
+            .ok_or_else(|| HealthError::not_found("record", record_id))?;
+        self.authorize(&record.owner_id, &[Role::Patient])?;
+
+        if anonymized_data.is_empty() {
+            return Err(HealthError::invalid_input("anonymized data must not be empty"));
+        }
+        if anonymized_data == record.data {
+            return Err(HealthError::invalid_input("anonymized data is identical to the source record"));
+        }
+
+        let subject = anonymize::subject_pseudonym(&self.pseudonym_key(), &record.owner_id);
+        self.store_derivative(record, subject, anonymized_data, AnonymizationMethod::Registered, Some(anonymization_proof))
+    }
+
+    // Which derivative was produced from which record; only the patient sees this link
+    pub fn get_anonymization_links(&self, patient_id: &str) -> Result<Vec<AnonymizationLink>, HealthError> {
+        self.authorize(patient_id, &[Role::Patient])?;
+
+        let mut links = Vec::new();
+        for record in self.patient_records_newest_first(patient_id)? {
+            if let Some(link) = self.anonymization_links.get(&record.record_id)? {
+                links.push(link);
+            }
+        }
+        Ok(links)
+    }
+
+    // Serves one participant's derivatives to the pool owner, never the
+    // source bytes. Subjects, derivative IDs and date shifts are scoped to the
+    // pool, so the same patient cannot be linked across pools.
+    pub fn get_pool_anonymized_data(
+        &mut self,
+        pool_id: &str,
+        participant_id: &str
+    ) -> Result<AccessResult<Vec<AnonymizedRecord>>, HealthError> {
+        env::log(&format!("Accessing anonymized data in pool: {}", pool_id));
+
+        let pool = self.research_pools.get(pool_id)?
+            .ok_or_else(|| HealthError::not_found("pool", pool_id))?;
+        self.authorize(&pool.entity_id, &[Role::Researcher])?;
+
+        let patient_id = self.resolve_participant(pool_id, participant_id)?
+            .ok_or_else(|| HealthError::not_found("participant", participant_id))?;
+        let submission = match self.pool_submissions.get(pool_id)? {
+            Some(submissions) => submissions.get(&patient_id)?,
+            None => None,
+        }
+        .ok_or_else(|| HealthError::not_found("participant", participant_id))?;
+
+        let purpose = pool_purpose(pool_id);
+        let key = self.pseudonym_key();
+        let shift = anonymize::pool_date_shift_days(&key, pool_id, &patient_id);
+        let now = time::now();
+        let mut derivatives = Vec::new();
+        let mut authorized = false;
+        let mut lapsed = false;
+
+        for record_id in &submission.record_ids {
+            let Some(record) = self.live_record(record_id)? else {
+                continue;
+            };
+            let Some(expires_at) = record.access_grants
+                .iter()
+                .find(|grant| grant.provider_id == pool.entity_id && grant.pool_id.as_deref() == Some(pool_id))
+                .map(|grant| grant.expires_at)
+            else {
+                continue;
+            };
+            if expires_at <= now {
+                lapsed = true;
+                continue;
+            }
+            authorized = true;
+
+            let Some(link) = self.anonymization_links.get(record_id)? else {
+                continue;
+            };
+            let Some(mut derivative) = self.anonymized_records.get(&link.anon_id)? else {
+                continue;
+            };
+            // Context-derived data embeds the patient-wide pseudonym and shift;
+            // derive it again for this pool
+            if derivative.method == AnonymizationMethod::Derived {
+                match derive_json(&record, participant_id, shift) {
+                    Some(data) => derivative.data = data,
+                    None => continue,
+                }
+            }
+            self.audit("get_pool_anonymized_data", &patient_id, Some(&derivative.anon_id), Some(&purpose), AccessOutcome::Granted)?;
+            derivative.anon_id = anonymize::pool_derivative_id(&key, pool_id, &derivative.anon_id);
+            derivative.subject_pseudonym = participant_id.to_string();
+            derivatives.push(derivative);
+        }
+
+        if !derivatives.is_empty() {
+            return Ok(AccessResult::Granted(derivatives));
+        }
+
+        let reason = if authorized {
+            DenialReason::NotAnonymized
+        } else if lapsed {
+            DenialReason::GrantExpired
+        } else {
+            DenialReason::NotAuthorized
+        };
+        self.audit("get_pool_anonymized_data", &patient_id, None, Some(&purpose), AccessOutcome::Denied)?;
+        Ok(AccessResult::Denied(reason))
+    }
+
+    // Withdraws the consent for one purpose, leaving grants and the entity's
+    // other consents in place
+    pub fn revoke_consent(
+        &mut self,
+        patient_id: &str,
+        entity_id: &str,
+        purpose: &str
+    ) -> Result<(), HealthError> {
+        self.authorize(patient_id, &[Role::Patient])?;
+
+        if is_pool_purpose(purpose) {
+            return Err(HealthError::invalid_input("withdraw the pool submission to end a research consent"));
+        }
+        let key = consent_key(patient_id, entity_id, purpose);
+        if self.consent_policies.remove(&key)?.is_none() {
+            return Err(HealthError::not_found("consent", key));
+        }
+
+        app::emit!(HealthEvent::ConsentRevoked {
+            patient_id,
+            entity_id,
+            purpose: &normalize_purpose(purpose)
+        });
+        Ok(())
+    }
+
+    // Revoke Access
+    pub fn revoke_access(
+        &mut self,
+        patient_id: &str,
+        entity_id: &str
+    ) -> Result<(), HealthError> {
+        self.authorize(patient_id, &[Role::Patient])?;
+
+        for mut record in self.patient_records_newest_first(patient_id)? {
+            record.access_grants.retain(|grant| grant.provider_id != entity_id);
+            self.records.insert(record.record_id.clone(), record)?;
+        }
+
+        // Drop the entity's consents for every purpose
+        let prefix = format!("{}:{}:", patient_id, entity_id);
+        let keys_to_remove: Vec<String> = self.consent_policies.entries()?
+            .filter_map(|(key, _)| key.starts_with(&prefix).then_some(key))
+            .collect();
+
+        for key in keys_to_remove {
+            self.consent_policies.remove(&key)?;
+        }
+        
+        Ok(())
+    }
+
+    pub fn get_patient_data(
+        &mut self,
+        patient_id: &str,
+        entity_id: &str
+    ) -> Result<AccessResult<PatientDataResponse>, HealthError> {
+        env::log(&format!("Attempting to access data for patient: {}", patient_id));
+        self.authorize_audited(entity_id, RECORD_READERS, "get_patient_data", patient_id, None, None)?;
+        
+        let records = self.patient_records_newest_first(patient_id)?;
+        let Some(latest) = records.first() else {
+            env::log(&format!("No record found for patient: {}", patient_id));
+            self.audit("get_patient_data", patient_id, None, None, AccessOutcome::Denied)?;
+            return Ok(AccessResult::Denied(DenialReason::NotFound));
+        };
+        let now = time::now();
+        let reason = latest.denial_reason(entity_id, now);
+
+        // Latest record visible to the entity
+        for record in records {
+            if record.is_visible_to(entity_id, now) {
+                env::log(&format!("Access granted for entity: {} to patient data: {}", entity_id, patient_id));
+                env::log(&format!("Record type: {}", record.record_type));
+                env::log(&format!("Timestamp: {}", record.timestamp));
+                
+                self.audit("get_patient_data", patient_id, Some(&record.record_id), None, AccessOutcome::Granted)?;
+                app::emit!(HealthEvent::RecordAccessed{ 
+                    patient_id,
+                    accessor_id: entity_id 
+                });
+    
+                return Ok(AccessResult::Granted(PatientDataResponse::from(record)));
+            }
+        }
+
+        env::log(&format!("Access denied for entity: {} to patient data: {}", entity_id, patient_id));
+        self.audit("get_patient_data", patient_id, None, None, AccessOutcome::Denied)?;
+        self.deny(patient_id, reason)
+    }
+
+    // Grants `entity_id` access until `expires_at` (Unix seconds), to one record or all of the patient's records
+    pub fn grant_access(
+        &mut self,
+        patient_id: String,
+        entity_id: String,
+        expires_at: u64,
+        record_id: Option<String>
+    ) -> Result<(), HealthError> {
+        self.authorize(&patient_id, &[Role::Patient])?;
+
+        let now = time::now();
+        let expires_at = time::from_unix_secs(expires_at);
+        if expires_at <= now {
+            return Err(HealthError::invalid_input("grant expiry must be in the future"));
+        }
+
+        let records = match record_id {
+            Some(record_id) => {
+                let record = self.live_record(&record_id)?
+                    .filter(|record| record.owner_id == patient_id)
+                    .ok_or_else(|| HealthError::not_found("record", &record_id))?;
+                vec![record]
+            }
+            None => self.patient_records_newest_first(&patient_id)?,
+        };
+        if records.is_empty() {
+            return Err(HealthError::not_found("patient records", &patient_id));
+        }
+
+        for mut record in records {
+            record.upsert_grant(&entity_id, now, expires_at, None);
+            self.records.insert(record.record_id.clone(), record)?;
+        }
+            
+        app::emit!(HealthEvent::AccessGranted { 
+            patient_id: &patient_id,
+            provider_id: &entity_id 
+        });
+        Ok(())
+    }
+
+    pub fn list_access_grants(
+        &self,
+        patient_id: &str,
+        include_expired: bool
+    ) -> Result<Vec<AccessGrantView>, HealthError> {
+        self.authorize(patient_id, &[Role::Patient])?;
+
+        let now = time::now();
+        let mut grants = Vec::new();
+
+        for record in self.patient_records_newest_first(patient_id)? {
+            for grant in record.access_grants {
+                let active = grant.expires_at > now;
+                if active || include_expired {
+                    grants.push(AccessGrantView {
+                        record_id: record.record_id.clone(),
+                        record_type: record.record_type.clone(),
+                        grant,
+                        active
+                    });
+                }
+            }
+        }
+
+        Ok(grants)
+    }
+
+    pub fn create_research_pool(
+        &mut self,
+        entity_id: String,
+        title: String,
+        description: String,
+        reward_amount: u64,
+        expiry_date: u64,
+        draft: Option<bool>,
+        max_participants: Option<u32>,
+        eligibility: Option<EligibilityCriteria>
+    ) -> Result<String, HealthError> {
+        env::log(&format!("Creating research pool: {} by entity: {}", title, entity_id));
+        self.authorize(&entity_id, &[Role::Researcher])?;
+
+        if max_participants == Some(0) {
+            return Err(HealthError::invalid_input("max participants must be positive"));
+        }
+        let eligibility = eligibility.unwrap_or_default();
+        eligibility.validate()?;
+
+        // Frontend sends the expiry as Unix seconds
+        let expiry_date = time::from_unix_secs(expiry_date);
+        if expiry_date <= time::now() {
+            return Err(HealthError::invalid_input("pool expiry must be in the future"));
+        }
+
+        let pool_id = self.next_id("pool");
+        let pool = ResearchPool {
+            pool_id: pool_id.clone(),
+            title: title.clone(),
+            description,
+            reward_amount,
+            entity_id: entity_id.clone(),
+            created_at: time::now(),
+            expiry_date,
+            // Drafts stay hidden from patients until the owner activates them
+            status: if draft.unwrap_or(false) { PoolStatus::Draft } else { PoolStatus::Active },
+            max_participants,
+            eligibility,
+        };
+
+        let mut entity_pools = self.entity_pools
+            .get(&entity_id)?
+            .unwrap_or_else(|| UnorderedMap::new());
+
+        entity_pools.insert(pool_id.clone(), pool.created_at)?;
+        self.entity_pools.insert(entity_id.clone(), entity_pools)?;
+        self.research_pools.insert(pool_id.clone(), pool)?;
+        self.pool_ledgers.insert(pool_id.clone(), PoolLedger {
+            pool_id: pool_id.clone(),
+            ..PoolLedger::default()
+        })?;
+
+        app::emit!(HealthEvent::PoolCreated { 
+            pool_id: &pool_id,
+            entity_id: &entity_id,
+            title: &title,
+            reward_amount,
+            expiry_date
+        });
+
+        Ok(pool_id)
+    }
+
+    pub fn get_research_pool(
+        &self,
+        pool_id: &str
+    ) -> Result<Option<ResearchPool>, HealthError> {
+        let caller = self.caller_id()?;
+        self.authorize(&caller, RECORD_READERS)?;
+
+        Ok(self.research_pools.get(pool_id)?.filter(|pool| pool.is_visible_to(&caller)))
+    }
+
+    // Pools owned by an entity, newest first. The owner sees every status,
+    // everyone else sees them once they leave draft.
+    pub fn list_entity_pools(&self, entity_id: &str) -> Result<Vec<ResearchPool>, HealthError> {
+        let caller = self.caller_id()?;
+        self.authorize(&caller, RECORD_READERS)?;
+
+        let Some(index) = self.entity_pools.get(entity_id)? else {
+            return Ok(Vec::new());
+        };
+
+        let mut pools = Vec::new();
+        for (pool_id, _) in index.entries()? {
+            if let Some(pool) = self.research_pools.get(&pool_id)? {
+                if pool.is_visible_to(&caller) {
+                    pools.push(pool);
+                }
+            }
+        }
+
+        pools.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(pools)
+    }
+
+    pub fn list_research_pools(&self) -> Result<Vec<ResearchPool>, HealthError> {
+    env::log("Listing all research pools");
+    
+    let mut pools = Vec::new();
+    let current_time = time::now();
+    
+ 
+    for (_, pool) in self.research_pools.entries()? {
+        if pool.status == PoolStatus::Active && pool.expiry_date > current_time {
+            pools.push(pool);
+        }
+    }
+    
+
+    pools.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    
+    env::log(&format!("Found {} active research pools", pools.len()));
+    Ok(pools)
+}
+
+    pub fn list_authorized_reports(
+        &mut self,
+        entity_id: &str
+    ) -> Result<Vec<PatientDataResponse>, HealthError> {
+        env::log(&format!("Listing authorized reports for entity: {}", entity_id));
+        self.authorize(entity_id, ENTITY_ROLES)?;
+        
+        let mut authorized_reports = Vec::new();
+        let now = time::now();
+      
+        let authorized: Vec<HealthRecord> = self.records.entries()?
+            .map(|(_, record)| record)
+            .filter(|record| record.deletion.is_none() && record.has_direct_grant(entity_id, now))
+            .collect();
+
+        for record in authorized {
+            self.audit("list_authorized_reports", &record.owner_id, Some(&record.record_id), None, AccessOutcome::Granted)?;
+            authorized_reports.push(PatientDataResponse::from(record));
+        }
+        
+        env::log(&format!("Found {} authorized reports", authorized_reports.len()));
+        Ok(authorized_reports)
+    }
+
+
+    pub fn delete_patient_data(&mut self, patient_id: &str) -> Result<(), HealthError> {
+        env::log(&format!("Deleting data for patient: {}", patient_id));
+        self.authorize(patient_id, &[Role::Patient])?;
+
+        let records = self.patient_records_newest_first(patient_id)?;
+        if records.is_empty() {
+            return Err(HealthError::not_found("patient records", patient_id));
+        }
+
+        let deleted_by = self.caller_id()?;
+        for record in records {
+            self.soft_delete(record, &deleted_by)?;
+        }
+        
+     
+        let keys_to_remove: Vec<String> = self.consent_policies.entries()?
+            .filter_map(|(key, _)| {
+                if key.starts_with(&format!("{}:", patient_id)) {
+                    Some(key)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        
+        for key in keys_to_remove {
+            self.consent_policies.remove(&key)?;
+        }
+        
+        Ok(())
+    }
+
+    // Retention and legal hold
+    pub fn get_retention_period(&self) -> Result<u64, HealthError> {
+        Ok(self.retention_secs)
+    }
+
+    // Applies to records deleted from now on; earlier deletions keep their purge date
+    pub fn set_retention_period(&mut self, retention_secs: u64) -> Result<(), HealthError> {
+        let caller = self.caller_id()?;
+        self.authorize(&caller, &[Role::Admin])?;
+
+        if !(MIN_RETENTION_SECS..=MAX_RETENTION_SECS).contains(&retention_secs) {
+            return Err(HealthError::invalid_input(format!(
+                "retention period must be between {} and {} seconds",
+                MIN_RETENTION_SECS, MAX_RETENTION_SECS
+            )));
+        }
+
+        self.retention_secs = retention_secs;
+        app::emit!(HealthEvent::RetentionPeriodChanged { retention_secs, changed_by: &caller });
+        Ok(())
+    }
+
+    pub fn place_legal_hold(&mut self, patient_id: String, reason: String) -> Result<(), HealthError> {
+        let caller = self.caller_id()?;
+        self.authorize(&caller, &[Role::Admin])?;
+
+        if reason.trim().is_empty() {
+            return Err(HealthError::invalid_input("legal hold reason must not be empty"));
+        }
+        if self.legal_holds.contains(&patient_id)? {
+            return Err(HealthError::already_exists("legal hold", &patient_id));
+        }
+
+        self.legal_holds.insert(patient_id.clone(), LegalHold {
+            patient_id: patient_id.clone(),
+            placed_by: caller.clone(),
+            reason: reason.trim().to_string(),
+            placed_at: time::now()
+        })?;
+
+        app::emit!(HealthEvent::LegalHoldPlaced { patient_id: &patient_id, placed_by: &caller });
+        Ok(())
+    }
+
+    pub fn release_legal_hold(&mut self, patient_id: &str) -> Result<(), HealthError> {
+        let caller = self.caller_id()?;
+        self.authorize(&caller, &[Role::Admin])?;
+
+        self.legal_holds
+            .remove(patient_id)?
+            .ok_or_else(|| HealthError::not_found("legal hold", patient_id))?;
+
+        app::emit!(HealthEvent::LegalHoldReleased { patient_id, released_by: &caller });
+        Ok(())
+    }
+
+    pub fn get_legal_hold(&self, patient_id: &str) -> Result<Option<LegalHold>, HealthError> {
+        let caller = self.caller_id()?;
+        if caller == patient_id {
+            self.authorize(patient_id, &[Role::Patient])?;
+        } else {
+            self.authorize(&caller, AUDIT_READERS)?;
+        }
+        Ok(self.legal_holds.get(patient_id)?)
+    }
+
+    // Permanently removes deleted records past their retention period, skipping
+    // patients under legal hold. Returns how many records were purged.
+    pub fn purge_deleted_records(&mut self, limit: Option<u32>) -> Result<u32, HealthError> {
+        let caller = self.caller_id()?;
+        self.authorize(&caller, &[Role::Admin])?;
+
+        let limit = limit.unwrap_or(MAX_PURGE_BATCH).clamp(1, MAX_PURGE_BATCH) as usize;
+        let now = time::now();
+
+        let expired: Vec<HealthRecord> = self.records.entries()?
+            .map(|(_, record)| record)
+            .filter(|record| record.deletion.as_ref().is_some_and(|deletion| deletion.purge_after <= now))
+            .collect();
+
+        let mut purged = 0;
+        for record in expired {
+            if purged as usize == limit {
+                break;
+            }
+            if self.legal_holds.contains(&record.owner_id)? {
+                env::log(&format!("Record {} is under legal hold; not purging", record.record_id));
+                continue;
+            }
+
+            self.remove_record(&record)?;
+            purged += 1;
+            app::emit!(HealthEvent::RecordPurged { patient_id: &record.owner_id, record_id: &record.record_id });
+        }
+
+        env::log(&format!("Purged {} deleted records", purged));
+        Ok(purged)
+    }
+
+    // Right to erasure: removes the patient's records (deleted or not) with
+    // their versions and anonymized derivatives, consents, pool submissions,
+    // reserved rewards, privacy settings, and finally the identity with its
+    // keys and roles. Paid rewards and audit entries must be kept, so the
+    // patient ID in them is replaced by a tombstone. The erased keys can
+    // register again as a new identity.
+    pub fn forget_patient(&mut self, patient_id: &str) -> Result<ErasureReceipt, HealthError> {
+        env::log("Processing erasure request");
+        let caller = self.caller_id()?;
+        if caller == patient_id {
+            self.authorize(patient_id, &[Role::Patient])?;
+        } else {
+            self.authorize(&caller, &[Role::Admin])?;
+        }
+
+        if self.legal_holds.contains(patient_id)? {
+            return Err(HealthError::invalid_state("patient data is under legal hold and cannot be erased"));
+        }
+        let is_admin = self.roles.get(patient_id)?.is_some_and(|roles| roles.contains(&Role::Admin));
+        if is_admin && !self.roles.entries()?.any(|(id, held)| id != patient_id && held.contains(&Role::Admin)) {
+            return Err(HealthError::invalid_state("cannot erase the last admin"));
+        }
+
+        let tombstone = anonymize::erasure_tombstone(&self.pseudonym_key(), patient_id);
+        let mut receipt = ErasureReceipt {
+            receipt_id: self.next_id("erasure"),
+            subject_tombstone: tombstone.clone(),
+            requested_by: if caller == patient_id { tombstone.clone() } else { caller },
+            erased_at: time::now(),
+            records: 0,
+            record_versions: 0,
+            anonymized_records: 0,
+            consents: 0,
+            pool_submissions: 0,
+            rewards_released: 0,
+            rewards_tombstoned: 0,
+            access_log_entries: 0,
+            identity_keys: 0
+        };
+
+        // Records, with their history and derivatives
+        let record_ids: Vec<String> = match self.patient_records.get(patient_id)? {
+            Some(index) => index.entries()?.map(|(record_id, _)| record_id).collect(),
+            None => Vec::new(),
+        };
+        for record_id in record_ids {
+            let Some(record) = self.records.get(&record_id)? else {
+                continue;
+            };
+            receipt.record_versions += self.record_versions(&record)?.len() as u32;
+            if self.anonymization_links.contains(&record_id)? {
+                receipt.anonymized_records += 1;
+            }
+            self.remove_record(&record)?;
+            receipt.records += 1;
+        }
+        self.patient_records.remove(patient_id)?;
+
+        // Consents, including those created for accepted pool submissions
+        let consent_keys: Vec<String> = self.consent_policies.entries()?
+            .filter(|(_, consent)| consent.patient_id == patient_id)
+            .map(|(key, _)| key)
+            .collect();
+        for key in consent_keys {
+            self.consent_policies.remove(&key)?;
+            receipt.consents += 1;
+        }
+
+        // Pool submissions
+        let pool_ids: Vec<String> = self.pool_submissions.entries()?.map(|(pool_id, _)| pool_id).collect();
+        for pool_id in pool_ids {
+            let Some(mut submissions) = self.pool_submissions.get(&pool_id)? else {
+                continue;
+            };
+            if submissions.remove(patient_id)?.is_some() {
+                self.pool_submissions.insert(pool_id, submissions)?;
+                receipt.pool_submissions += 1;
+            }
+        }
+
+        // Rewards: reserved ones go back to the pool, paid ones stay on the books
+        let rewards: Vec<RewardEntry> = self.rewards.entries()?
+            .map(|(_, reward)| reward)
+            .filter(|reward| reward.patient_id == patient_id)
+            .collect();
+        for mut reward in rewards {
+            if reward.status == RewardStatus::Reserved {
+                self.release_reward(&reward.pool_id, patient_id)?;
+                receipt.rewards_released += 1;
+            } else {
+                self.rewards.remove(&reward_key(&reward.pool_id, patient_id))?;
+                reward.patient_id = tombstone.clone();
+                self.rewards.insert(reward_key(&reward.pool_id, &tombstone), reward)?;
+                receipt.rewards_tombstoned += 1;
+            }
+        }
+
+        self.privacy_settings.remove(patient_id)?;
+        receipt.access_log_entries = self.tombstone_access_log(patient_id, &tombstone)?;
+
+        // The identity goes last, so nothing above runs for an unbound key
+        if let Some(profile) = self.identities.remove(patient_id)? {
+            for key in &profile.keys {
+                self.identity_keys.remove(key)?;
+                receipt.identity_keys += 1;
+            }
+        }
+        self.roles.remove(patient_id)?;
+
+        self.erasure_receipts.insert(receipt.receipt_id.clone(), receipt.clone())?;
+        app::emit!(HealthEvent::PatientForgotten {
+            receipt_id: &receipt.receipt_id,
+            subject_tombstone: &tombstone
+        });
+        Ok(receipt)
+    }
+
+    // Data portability: everything stored about the calling patient in one
+    // checksummed document
+    pub fn export_patient_bundle(&self, patient_id: &str) -> Result<PatientBundle, HealthError> {
+        self.authorize(patient_id, &[Role::Patient])?;
+
+        let mut records = Vec::new();
+        if let Some(index) = self.patient_records.get(patient_id)? {
+            for (record_id, _) in index.entries()? {
+                if let Some(record) = self.records.get(&record_id)? {
+                    records.push(record);
+                }
+            }
+        }
+        records.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+        let now = time::now();
+        let mut record_versions = Vec::new();
+        let mut grants = Vec::new();
+        for record in &records {
+            record_versions.extend(self.record_versions(record)?);
+            grants.extend(record.access_grants.iter().map(|grant| AccessGrantView {
+                record_id: record.record_id.clone(),
+                record_type: record.record_type.clone(),
+                grant: grant.clone(),
+                active: grant.expires_at > now
+            }));
+        }
+
+        let consents = self.consent_policies.entries()?
+            .map(|(_, consent)| consent)
+            .filter(|consent| consent.patient_id == patient_id)
+            .collect();
+
+        let mut pool_submissions = Vec::new();
+        for (_, submissions) in self.pool_submissions.entries()? {
+            if let Some(submission) = submissions.get(patient_id)? {
+                pool_submissions.push(submission);
+            }
+        }
+
+        let access_log = self.access_log_newest_first(|entry| {
+            entry.patient_id == patient_id || entry.accessor_id == patient_id
+        })?;
+
+        let mut bundle = PatientBundle {
+            format: PATIENT_BUNDLE_FORMAT.to_string(),
+            format_version: PATIENT_BUNDLE_VERSION,
+            patient_id: patient_id.to_string(),
+            source_context: bs58::encode(env::context_id()).into_string(),
+            exported_at: now,
+            records,
+            record_versions,
+            consents,
+            grants,
+            pool_submissions,
+            access_log,
+            checksum: String::new()
+        };
+        bundle.checksum = bundle.compute_checksum()?;
+        Ok(bundle)
+    }
+
+    // Imports records and their history from another context's export under
+    // new IDs. Consents, grants, submissions and the access log refer to
+    // entities and pools of the source context, so they are not replayed.
+    pub fn import_patient_bundle(
+        &mut self,
+        patient_id: String,
+        bundle: PatientBundle
+    ) -> Result<BundleImportSummary, HealthError> {
+        env::log(&format!("Importing patient bundle for patient: {}", patient_id));
+        self.authorize(&patient_id, &[Role::Patient])?;
+
+        if bundle.format != PATIENT_BUNDLE_FORMAT || bundle.format_version != PATIENT_BUNDLE_VERSION {
+            return Err(HealthError::invalid_input(format!(
+                "unsupported bundle format {} version {}", bundle.format, bundle.format_version
+            )));
+        }
+        if bundle.compute_checksum()? != bundle.checksum {
+            return Err(HealthError::invalid_input("bundle checksum does not match its contents"));
+        }
+
+        // Validate everything before storing anything, versions included
+        let bundle_record_ids: BTreeSet<String> = bundle.records.iter().map(|record| record.record_id.clone()).collect();
+        let mut live = Vec::new();
+        let mut skipped_deleted = 0;
+        for record in bundle.records {
+            if record.owner_id != bundle.patient_id {
+                return Err(HealthError::invalid_input(format!(
+                    "bundle record {} belongs to another patient", record.record_id
+                )));
+            }
+            clinical::validate_entries(&record.record_type, &record.structured)?;
+            if record.content_hash != content_hash(&record.data) {
+                return Err(HealthError::invalid_input(format!(
+                    "bundle record {} does not match its content hash", record.record_id
+                )));
+            }
+            if record.deletion.is_some() {
+                skipped_deleted += 1;
+            } else {
+                live.push(record);
+            }
+        }
+        let mut seen_versions = BTreeSet::new();
+        for version in &bundle.record_versions {
+            if !bundle_record_ids.contains(version.record_id.as_str()) {
+                return Err(HealthError::invalid_input(format!(
+                    "bundle version {} of {} belongs to no bundle record", version.version, version.record_id
+                )));
+            }
+            if !seen_versions.insert((version.record_id.as_str(), version.version)) {
+                return Err(HealthError::invalid_input(format!(
+                    "bundle has version {} of {} more than once", version.version, version.record_id
+                )));
+            }
+            clinical::validate_entries(&version.record_type, &version.structured)?;
+            if version.content_hash != content_hash(&version.data) {
+                return Err(HealthError::invalid_input(format!(
+                    "bundle version {} of {} does not match its content hash", version.version, version.record_id
+                )));
+            }
+        }
+
+        let mut summary = BundleImportSummary { record_ids: Vec::new(), record_versions: 0, skipped_deleted };
+        for source in live {
+            let record_id = self.next_id("rec");
+            let record = HealthRecord {
+                record_id: record_id.clone(),
+                owner_id: patient_id.clone(),
+                access_grants: Vec::new(),
+                is_anonymized: false,
+                consent_proof: None,
+                deletion: None,
+                ..source.clone()
+            };
+
+            let mut history: Vec<RecordVersion> = bundle.record_versions
+                .iter()
+                .filter(|version| version.record_id == source.record_id)
+                .cloned()
+                .collect();
+            history.sort_by_key(|version| version.version);
+
+            if history.is_empty() {
+                self.push_version(&record, &patient_id, None)?;
+            } else {
+                let mut versions = Vector::new();
+                for mut version in history {
+                    version.record_id = record_id.clone();
+                    // The bundle cannot prove who wrote a version, so the
+                    // importer answers for it and the claim is kept aside
+                    version.imported_from = Some(ImportedFrom {
+                        source_context: bundle.source_context.clone(),
+                        claimed_author_id: std::mem::replace(&mut version.author_id, patient_id.clone())
+                    });
+                    versions.push(version)?;
+                    summary.record_versions += 1;
+                }
+                self.record_versions.insert(record_id.clone(), versions)?;
+            }
+
+            self.add_record(record)?;
+            summary.record_ids.push(record_id);
+        }
+
+        env::log(&format!("Imported {} records from context {}", summary.record_ids.len(), bundle.source_context));
+        Ok(summary)
+    }
+
+    pub fn get_erasure_receipt(&self, receipt_id: &str) -> Result<ErasureReceipt, HealthError> {
+        let caller = self.caller_id()?;
+        self.authorize(&caller, AUDIT_READERS)?;
+
+        self.erasure_receipts
+            .get(receipt_id)?
+            .ok_or_else(|| HealthError::not_found("erasure receipt", receipt_id))
+    }
+
+    pub fn delete_research_pool(&mut self, pool_id: &str) -> Result<(), HealthError> {
+        env::log(&format!("Deleting research pool: {}", pool_id));
+        
+        if let Some(pool) = self.research_pools.get(pool_id)? {
+            // Verify ownership
+            self.authorize(&pool.entity_id, &[Role::Researcher])?;
+
+            let ledger = self.pool_ledger(pool_id)?;
+            if ledger.reserved > 0 {
+                return Err(HealthError::invalid_state(format!(
+                    "pool {} still owes {} in unclaimed rewards", pool_id, ledger.reserved
+                )));
+            }
+            
+            self.revoke_pool_access(&pool)?;
+            self.research_pools.remove(pool_id)?;
+            self.pool_submissions.remove(pool_id)?;
+            self.pool_ledgers.remove(pool_id)?;
+
+            if let Some(mut index) = self.entity_pools.get(&pool.entity_id)? {
+                index.remove(pool_id)?;
+                self.entity_pools.insert(pool.entity_id.clone(), index)?;
+            }
+            
+            app::emit!(HealthEvent::PoolDeleted { 
+                pool_id,
+                entity_id: &pool.entity_id,
+                title: &pool.title 
+            });
+            Ok(())
+        } else {
+            Err(HealthError::not_found("pool", pool_id))
+        }
+    }
+
+
+    pub fn update_patient_data(
+        &mut self,
+        patient_id: &str,
+        new_data: Vec<u8>,
+        record_type: RecordType,
+        structured: Option<Vec<ClinicalEntry>>,
+        reason: Option<String>
+    ) -> Result<(), HealthError> {
+        env::log(&format!("Updating latest record for patient: {}", patient_id));
+        
+        let latest = self.patient_records_newest_first(patient_id)?
+            .into_iter()
+            .next()
+            .ok_or_else(|| HealthError::not_found("patient records", patient_id))?;
+
+        self.update_record(&latest.record_id, new_data, record_type, structured, reason)
+    }
+
+
+    pub fn update_research_pool(
+        &mut self,
+        pool_id: &str,
+        title: Option<String>,
+        description: Option<String>,
+        reward_amount: Option<u64>,
+        status: Option<PoolStatus>,
+        max_participants: Option<u32>,
+        eligibility: Option<EligibilityCriteria>
+    ) -> Result<(), HealthError> {
+        env::log(&format!("Updating research pool: {}", pool_id));
+        
+        if let Some(mut pool) = self.research_pools.get(pool_id)? {
+            // Verify ownership
+            self.authorize(&pool.entity_id, &[Role::Researcher])?;
+
+            if pool.status.is_terminal() {
+                return Err(HealthError::invalid_state(format!("pool {} is {}", pool_id, pool.status)));
+            }
+            // Validate every input before changing anything, access included
+            let next = status.filter(|next| *next != pool.status);
+            if let Some(next) = next {
+                if !pool.status.can_transition_to(next) {
+                    return Err(HealthError::invalid_state(format!(
+                        "pool cannot move from {} to {}", pool.status, next
+                    )));
+                }
+            }
+            if let Some(max) = max_participants {
+                let participants = self.participant_count(pool_id)?;
+                if max == 0 || max < participants {
+                    return Err(HealthError::invalid_input(format!(
+                        "max participants must be at least the current {} participants", participants
+                    )));
+                }
+            }
+            if let Some(criteria) = &eligibility {
+                criteria.validate()?;
+            }
+
+            // Update fields if provided
+            if let Some(t) = title { pool.title = t; }
+            if let Some(d) = description { pool.description = d; }
+            if let Some(r) = reward_amount { pool.reward_amount = r; }
+            if let Some(max) = max_participants { pool.max_participants = Some(max); }
+            if let Some(criteria) = eligibility { pool.eligibility = criteria; }
+            if let Some(next) = next {
+                pool.status = next;
+                if next.is_terminal() {
+                    self.revoke_pool_access(&pool)?;
+                }
+            }
+
+            self.research_pools.insert(pool_id.to_string(), pool.clone())?;
+            
+            app::emit!(HealthEvent::PoolUpdated { 
+                pool_id,
+                entity_id: &pool.entity_id,
+                title: &pool.title,
+                status: pool.status
+            });
+            Ok(())
+        } else {
+            Err(HealthError::not_found("pool", pool_id))
+        }
+    }
+
+    pub fn submit_to_pool(
+        &mut self,
+        pool_id: &str,
+        patient_id: &str,
+        record_ids: Vec<String>,
+    ) -> Result<(), HealthError> {
+        self.authorize(patient_id, &[Role::Patient])?;
+
+        // Get pool and check expiry
+        let pool = self.research_pools.get(pool_id)?
+            .ok_or_else(|| HealthError::not_found("pool", pool_id))?;
+        
+        let current_time = time::now();
+        if current_time >= pool.expiry_date {
+            return Err(HealthError::expired("pool", pool_id));
+        }
+        if pool.status != PoolStatus::Active {
+            return Err(HealthError::invalid_state(format!(
+                "pool {} is {} and not accepting submissions", pool_id, pool.status
+            )));
+        }
+
+        let mut record_ids = record_ids;
+        record_ids.sort();
+        record_ids.dedup();
+        if record_ids.is_empty() {
+            return Err(HealthError::invalid_input("select at least one record to contribute"));
+        }
+
+        let mut records = Vec::new();
+        for record_id in &record_ids {
+            match self.live_record(record_id)? {
+                Some(record) if record.owner_id == patient_id => records.push(record),
+                _ => return Err(HealthError::not_found("record", record_id)),
+            }
+        }
+    
+        let mut submissions = self.pool_submissions
+            .get(pool_id)?
+            .unwrap_or_else(|| UnorderedMap::new());
+
+        // A finished submission may be resubmitted, an open one may not. The
+        // new submission keeps the earlier history so the trail stays complete.
+        let history = match submissions.get(patient_id)? {
+            Some(existing) if !existing.status.is_terminal() => {
+                return Err(HealthError::already_exists("submission", format!("{}:{}", pool_id, patient_id)));
+            }
+            Some(existing) => existing.history,
+            None => Vec::new(),
+        };
+
+        let mut submission = PoolSubmission {
+            pool_id: pool_id.to_string(),
+            patient_id: patient_id.to_string(),
+            entity_id: pool.entity_id.clone(),
+            submitted_at: current_time,
+            status: SubmissionStatus::Pending,
+            record_ids,
+            history,
+        };
+        submission.set_status(SubmissionStatus::Pending, patient_id, None);
+
+        if let Some(max) = pool.max_participants {
+            if self.participant_count(pool_id)? >= max {
+                return Err(HealthError::NotEligible {
+                    pool_id: pool_id.to_string(),
+                    reason: format!("the pool has reached its limit of {} participants", max),
+                });
+            }
+        }
+
+        // Eligibility is judged on the contributed records only
+        if let Some(reason) = pool.eligibility.rejection_reason(&records, current_time) {
+            return Err(HealthError::NotEligible { pool_id: pool_id.to_string(), reason });
+        }
+        
+        submissions.insert(patient_id.to_string(), submission)?;
+        self.pool_submissions.insert(pool_id.to_string(), submissions)?;
+    
+        app::emit!(HealthEvent::PoolSubmission {
+            pool_id,
+            participant_id: &self.participant_id(pool_id, patient_id),
+            entity_id: &pool.entity_id,
+            status: SubmissionStatus::Pending
+        });
+    
+        Ok(())
+    }
+
+    // Researchers address submissions by the participant ID they see in
+    // `get_pool_submissions`, never by patient ID
+    pub fn update_submission_status(
+        &mut self,
+        pool_id: &str,
+        participant_id: &str,
+        status: SubmissionStatus,
+        reason: Option<String>,
+    ) -> Result<(), HealthError> {
+        if status == SubmissionStatus::Withdrawn {
+            return Err(HealthError::invalid_input("only the patient can withdraw a submission"));
+        }
+        if status == SubmissionStatus::Rewarded {
+            return Err(HealthError::invalid_input("submissions become rewarded when the patient claims the reward"));
+        }
+
+        let pool = self.research_pools.get(pool_id)?
+            .ok_or_else(|| HealthError::not_found("pool", pool_id))?;
+
+        // Only the pool owner reviews submissions, and only while the pool runs
+        self.authorize(&pool.entity_id, &[Role::Researcher])?;
+        if pool.status.is_terminal() {
+            return Err(HealthError::invalid_state(format!("pool {} is {}", pool_id, pool.status)));
+        }
+        if time::now() >= pool.expiry_date {
+            return Err(HealthError::expired("pool", pool_id));
+        }
+
+        let mut submissions = self.pool_submissions
+            .get(pool_id)?
+            .ok_or_else(|| HealthError::not_found("pool submissions", pool_id))?;
+
+        let patient_id = self.resolve_participant(pool_id, participant_id)?
+            .ok_or_else(|| HealthError::not_found("submission", format!("{}:{}", pool_id, participant_id)))?;
+        let patient_id = patient_id.as_str();
+      
+        if let Some(mut submission) = submissions.get(patient_id)? {
+            if !submission.status.can_transition_to(status) {
+                return Err(HealthError::invalid_state(format!(
+                    "submission cannot move from {} to {}", submission.status, status
+                )));
+            }
+            if status == SubmissionStatus::Accepted {
+                self.reserve_reward(&pool, patient_id)?;
+                self.grant_pool_access(&pool, &submission)?;
+            }
+
+            submission.set_status(status, &pool.entity_id, reason);
+            submissions.insert(patient_id.to_string(), submission)?;
+            self.pool_submissions.insert(pool_id.to_string(), submissions)?;
+
+            app::emit!(HealthEvent::SubmissionUpdated {
+                pool_id,
+                participant_id,
+                entity_id: &pool.entity_id,
+                status
+            });
+            
+            Ok(())
+        } else {
+            Err(HealthError::not_found("submission", format!("{}:{}", pool_id, participant_id)))
+        }
+    }
+
+    // Lets a patient leave a pool. Anything shared on acceptance is revoked and
+    // an unclaimed reward goes back to the pool budget; rejoining needs a new submission.
+    pub fn withdraw_submission(
+        &mut self,
+        pool_id: &str,
+        patient_id: &str,
+        reason: Option<String>,
+    ) -> Result<(), HealthError> {
+        self.authorize(patient_id, &[Role::Patient])?;
+
+        let pool = self.research_pools.get(pool_id)?
+            .ok_or_else(|| HealthError::not_found("pool", pool_id))?;
+
+        let mut submissions = self.pool_submissions
+            .get(pool_id)?
+            .ok_or_else(|| HealthError::not_found("submission", format!("{}:{}", pool_id, patient_id)))?;
+        let mut submission = submissions.get(patient_id)?
+            .ok_or_else(|| HealthError::not_found("submission", format!("{}:{}", pool_id, patient_id)))?;
+
+        if !submission.status.can_transition_to(SubmissionStatus::Withdrawn) {
+            return Err(HealthError::invalid_state(format!(
+                "a {} submission cannot be withdrawn", submission.status
+            )));
+        }
+
+        if submission.status == SubmissionStatus::Accepted {
+            self.revoke_submission_access(&pool, &submission)?;
+            self.release_reward(pool_id, patient_id)?;
+        }
+
+        submission.set_status(SubmissionStatus::Withdrawn, patient_id, reason);
+        submissions.insert(patient_id.to_string(), submission)?;
+        self.pool_submissions.insert(pool_id.to_string(), submissions)?;
+
+        app::emit!(HealthEvent::SubmissionWithdrawn {
+            pool_id,
+            participant_id: &self.participant_id(pool_id, patient_id),
+            entity_id: &pool.entity_id
+        });
+
+        Ok(())
+    }
+
+    // Submissions to a pool, visible to the pool owner only, with patients
+    // replaced by their participant IDs. An empty or missing `statuses` list
+    // returns submissions in every status.
+    pub fn get_pool_submissions(
+        &self,
+        pool_id: &str,
+        statuses: Option<Vec<SubmissionStatus>>,
+        offset: Option<u32>,
+        limit: Option<u32>
+    ) -> Result<Page<PoolSubmission>, HealthError> {
+        let pool = self.research_pools.get(pool_id)?
+            .ok_or_else(|| HealthError::not_found("pool", pool_id))?;
+
+        self.authorize(&pool.entity_id, &[Role::Researcher])?;
+
+        let statuses = statuses.unwrap_or_default();
+        let mut matching = Vec::new();
+
+        if let Some(submissions) = self.pool_submissions.get(pool_id)? {
+            for (_, submission) in submissions.entries()? {
+                if statuses.is_empty() || statuses.contains(&submission.status) {
+                    matching.push(self.pseudonymize_submission(submission));
+                }
+            }
+        }
+
+        matching.sort_by(|a, b| b.submitted_at.cmp(&a.submitted_at));
+        Ok(paginate(matching, offset, limit))
+    }
+
+    pub fn get_patient_submissions(
+        &self,
+        patient_id: &str
+    ) -> Result<Vec<SubmissionWithPool>, HealthError> {
+        self.authorize(patient_id, &[Role::Patient])?;
+
+        let mut submissions = Vec::new();
+        
+        for (pool_id, pool_submissions) in self.pool_submissions.entries()? {
+            if let Some(submission) = pool_submissions.get(patient_id)? {
+            
+                if let Some(pool) = self.research_pools.get(&pool_id)? {
+                    submissions.push(SubmissionWithPool {
+                        submission,
+                        pool_title: pool.title,
+                        pool_description: pool.description,
+                        reward_amount: pool.reward_amount,
+                        expiry_date: pool.expiry_date
+                    });
+                }
+            }
+        }
+
+        submissions.sort_by(|a, b| b.submission.submitted_at.cmp(&a.submission.submitted_at));
+        Ok(submissions)
+    }  
+
+    // Participant IDs
+    // A patient can look up their own participant ID in a pool
+    pub fn get_participant_id(&self, pool_id: &str, patient_id: &str) -> Result<String, HealthError> {
+        self.authorize(patient_id, &[Role::Patient])?;
+
+        if self.research_pools.get(pool_id)?.is_none() {
+            return Err(HealthError::not_found("pool", pool_id));
+        }
+        Ok(self.participant_id(pool_id, patient_id))
+    }
+
+    // Maps a participant ID back to the patient. Reserved to honest brokers
+    // and recorded in the access log.
+    pub fn reidentify_participant(
+        &mut self,
+        pool_id: &str,
+        participant_id: &str
+    ) -> Result<String, HealthError> {
+        let caller = self.caller_id()?;
+        self.authorize(&caller, &[Role::HonestBroker])?;
+
+        let patient_id = self.resolve_participant(pool_id, participant_id)?
+            .ok_or_else(|| HealthError::not_found("participant", format!("{}:{}", pool_id, participant_id)))?;
+
+        self.audit("reidentify_participant", &patient_id, None, Some(&pool_purpose(pool_id)), AccessOutcome::Granted)?;
+        Ok(patient_id)
+    }
+
+    // Reward Settlement
+    // `fund_pool` and `claim_reward` are the context-side counterparts of the
+    // Starknet `deposit_reward` and `withdraw_reward` calls; the transaction
+    // hash is recorded so both ledgers can be reconciled.
+    pub fn fund_pool(
+        &mut self,
+        pool_id: &str,
+        amount: u64,
+        deposit_ref: Option<String>
+    ) -> Result<PoolLedger, HealthError> {
+        let pool = self.research_pools.get(pool_id)?
+            .ok_or_else(|| HealthError::not_found("pool", pool_id))?;
+
+        self.authorize(&pool.entity_id, &[Role::Researcher])?;
+
+        if amount == 0 {
+            return Err(HealthError::invalid_input("funding amount must be positive"));
+        }
+        if pool.status.is_terminal() {
+            return Err(HealthError::invalid_state(format!("pool {} is {}", pool_id, pool.status)));
+        }
+        let deposit_ref = deposit_ref.map(|tx| tx.trim().to_string());
+        if let Some(tx) = &deposit_ref {
+            if tx.is_empty() {
+                return Err(HealthError::invalid_input("deposit reference must not be empty"));
+            }
+            // A deposit funds one pool, once
+            let recorded = self.pool_ledgers.entries()?
+                .any(|(_, ledger)| ledger.deposit_refs.contains(tx));
+            if recorded {
+                return Err(HealthError::already_exists("deposit", tx.clone()));
+            }
+        }
+
+        let mut ledger = self.pool_ledger(pool_id)?;
+        ledger.funded = ledger.funded
+            .checked_add(amount)
+            .ok_or_else(|| HealthError::invalid_input("pool budget overflow"))?;
+        if let Some(tx) = deposit_ref {
+            ledger.deposit_refs.push(tx);
+        }
+        self.pool_ledgers.insert(pool_id.to_string(), ledger.clone())?;
+
+        app::emit!(HealthEvent::PoolFunded {
+            pool_id,
+            amount,
+            funded: ledger.funded
+        });
+
+        Ok(ledger)
+    }
+
+    // Budgets are only shown to the pool owner
+    pub fn get_pool_ledger(&self, pool_id: &str) -> Result<PoolLedger, HealthError> {
+        let pool = self.research_pools.get(pool_id)?
+            .ok_or_else(|| HealthError::not_found("pool", pool_id))?;
+        self.authorize(&pool.entity_id, &[Role::Researcher])?;
+
+        self.pool_ledger(pool_id)
+    }
+
+    pub fn claim_reward(
+        &mut self,
+        pool_id: &str,
+        patient_id: &str,
+        settlement_ref: Option<String>
+    ) -> Result<RewardEntry, HealthError> {
+        self.authorize(patient_id, &[Role::Patient])?;
+
+        let key = reward_key(pool_id, patient_id);
+        let mut reward = self.rewards.get(&key)?
+            .ok_or_else(|| HealthError::not_found("reward", &key))?;
+
+        if reward.status == RewardStatus::Paid {
+            return Err(HealthError::invalid_state(format!("reward {} has already been paid", key)));
+        }
+
+        let mut ledger = self.pool_ledger(pool_id)?;
+        ledger.reserved = ledger.reserved.saturating_sub(reward.amount);
+        ledger.paid = ledger.paid.saturating_add(reward.amount);
+
+        reward.status = RewardStatus::Paid;
+        reward.paid_at = Some(time::now());
+        reward.settlement_ref = settlement_ref;
+
+        if let Some(mut submissions) = self.pool_submissions.get(pool_id)? {
+            if let Some(mut submission) = submissions.get(patient_id)? {
+                submission.set_status(SubmissionStatus::Rewarded, patient_id, None);
+                submissions.insert(patient_id.to_string(), submission)?;
+                self.pool_submissions.insert(pool_id.to_string(), submissions)?;
+            }
+        }
+
+        self.pool_ledgers.insert(pool_id.to_string(), ledger)?;
+        self.rewards.insert(key, reward.clone())?;
+
+        app::emit!(HealthEvent::RewardPaid {
+            pool_id,
+            participant_id: &self.participant_id(pool_id, patient_id),
+            amount: reward.amount
+        });
+
+        Ok(reward)
+    }
+
+    pub fn get_reward_balance(&self, patient_id: &str) -> Result<RewardBalance, HealthError> {
+        self.authorize(patient_id, &[Role::Patient])?;
+
+        let mut balance = RewardBalance {
+            patient_id: patient_id.to_string(),
+            claimable: 0,
+            paid: 0,
+            rewards: Vec::new(),
+        };
+
+        for (_, reward) in self.rewards.entries()? {
+            if reward.patient_id != patient_id {
+                continue;
+            }
+            match reward.status {
+                RewardStatus::Reserved => balance.claimable = balance.claimable.saturating_add(reward.amount),
+                RewardStatus::Paid => balance.paid = balance.paid.saturating_add(reward.amount),
+            }
+            balance.rewards.push(reward);
+        }
+
+        balance.rewards.sort_by(|a, b| b.reserved_at.cmp(&a.reserved_at));
+        Ok(balance)
+    }
+}
+
+impl HealthDataStore {
+    /// Base58 public key of the executor invoking the current call.
+    fn executor_key(&self) -> String {
+        bs58::encode(env::executor_id()).into_string()
+    }
+
+    /// Identity the executor's key is bound to in the identity registry.
+    fn caller_id(&self) -> Result<String, HealthError> {
+        let key = self.executor_key();
+        match self.identity_keys.get(&key)? {
+            Some(identity_id) => Ok(identity_id),
+            None => Err(HealthError::UnregisteredKey { key }),
+        }
+    }
+
+    /// Rejects the call unless the executor is acting as `claimed_id`
+    /// and that identity holds at least one of `roles`.
+    fn authorize(&self, claimed_id: &str, roles: &[Role]) -> Result<(), HealthError> {
+        let caller = self.caller_id()?;
+        if caller != claimed_id {
+            env::log(&format!("Rejected call from {} acting as {}", caller, claimed_id));
+            return Err(HealthError::Unauthorized {
+                caller,
+                acting_as: claimed_id.to_string(),
+            });
+        }
+
+        let held = self.roles.get(&caller)?.unwrap_or_default();
+        if !roles.iter().any(|role| held.contains(role)) {
+            env::log(&format!("Rejected call from {} lacking roles {:?}", caller, roles));
+            return Err(HealthError::MissingRole {
+                identity_id: caller,
+                required: roles.to_vec(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Like `authorize`, but records a denied access attempt before failing.
+    fn authorize_audited(
+        &mut self,
+        claimed_id: &str,
+        roles: &[Role],
+        method: &str,
+        patient_id: &str,
+        record_id: Option<&str>,
+        purpose: Option<&str>,
+    ) -> Result<(), HealthError> {
+        if let Err(err) = self.authorize(claimed_id, roles) {
+            self.audit(method, patient_id, record_id, purpose, AccessOutcome::Denied)?;
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    /// Appends an entry to the access log. Entries are never removed; the only
+    /// change ever made to one is `forget_patient` replacing an erased
+    /// patient's ID with their tombstone.
+    fn audit(
+        &mut self,
+        method: &str,
+        patient_id: &str,
+        record_id: Option<&str>,
+        purpose: Option<&str>,
+        outcome: AccessOutcome,
+    ) -> Result<(), HealthError> {
+        let accessor_id = self.caller_id().unwrap_or_else(|_| self.executor_key());
+        let entry = AccessLogEntry {
+            sequence: self.access_log.len()? as u64,
+            accessor_id,
+            patient_id: patient_id.to_string(),
+            record_id: record_id.map(str::to_string),
+            method: method.to_string(),
+            purpose: purpose.map(str::to_string),
+            outcome,
+            timestamp: time::now(),
+        };
+
+        self.access_log.push(entry)?;
+        Ok(())
+    }
+
+    fn access_log_newest_first(
+        &self,
+        filter: impl Fn(&AccessLogEntry) -> bool,
+    ) -> Result<Vec<AccessLogEntry>, HealthError> {
+        let mut entries: Vec<AccessLogEntry> = self.access_log.iter()?.filter(|entry| filter(entry)).collect();
+        entries.reverse();
+        Ok(entries)
+    }
+
+    /// Rewrites audit entries naming `patient_id` to name `tombstone` instead.
+    /// The log is cleared and refilled in place, so no copy of the original
+    /// entries stays in storage, and entries keep their sequence. Returns how
+    /// many entries changed.
+    fn tombstone_access_log(&mut self, patient_id: &str, tombstone: &str) -> Result<u32, HealthError> {
+        let entries: Vec<AccessLogEntry> = self.access_log.iter()?.collect();
+        let rewritten = entries
+            .iter()
+            .filter(|entry| entry.patient_id == patient_id || entry.accessor_id == patient_id)
+            .count();
+        if rewritten == 0 {
+            return Ok(0);
+        }
+
+        self.access_log.clear()?;
+        for mut entry in entries {
+            if entry.patient_id == patient_id {
+                entry.patient_id = tombstone.to_string();
+            }
+            if entry.accessor_id == patient_id {
+                entry.accessor_id = tombstone.to_string();
+            }
+            self.access_log.push(entry)?;
+        }
+        Ok(rewritten as u32)
+    }
+
+    /// Refuses access with `reason`, or with `NotFound` if the patient hides record existence.
+    fn deny<T>(&self, patient_id: &str, reason: DenialReason) -> Result<AccessResult<T>, HealthError> {
+        let hidden = self.privacy_settings
+            .get(patient_id)?
+            .is_some_and(|settings| settings.hide_record_existence);
+
+        if hidden {
+            return Ok(AccessResult::Denied(DenialReason::NotFound));
+        }
+        Ok(AccessResult::Denied(reason))
+    }
+
+    fn add_role(&mut self, identity_id: &str, role: Role) -> Result<(), HealthError> {
+        let mut roles = self.roles.get(identity_id)?.unwrap_or_default();
+        if !roles.contains(&role) {
+            roles.push(role);
+            self.roles.insert(identity_id.to_string(), roles)?;
+            app::emit!(HealthEvent::RoleAssigned { identity_id, role });
+        }
+        Ok(())
+    }
+
+    fn any_admin(&self) -> Result<bool, HealthError> {
+        Ok(self.roles.entries()?.any(|(_, roles)| roles.contains(&Role::Admin)))
+    }
+
+    /// Shares the submitted records with the pool owner until the pool expires,
+    /// backed by a consent scoped to this pool.
+    fn grant_pool_access(&mut self, pool: &ResearchPool, submission: &PoolSubmission) -> Result<(), HealthError> {
+        let now = time::now();
+        let purpose = pool_purpose(&pool.pool_id);
+
+        self.consent_policies.insert(
+            consent_key(&submission.patient_id, &pool.entity_id, &purpose),
+            ConsentPolicy {
+                patient_id: submission.patient_id.clone(),
+                entity_id: pool.entity_id.clone(),
+                purpose,
+                expiration: pool.expiry_date,
+                proof: String::new(), // Backed by the accepted submission, not a Starknet proof
+            },
+        )?;
+
+        for record_id in &submission.record_ids {
+            let Some(mut record) = self.live_record(record_id)? else {
+                continue;
+            };
+            record.upsert_grant(&pool.entity_id, now, pool.expiry_date, Some(&pool.pool_id));
+            self.records.insert(record_id.clone(), record)?;
+        }
+
+        Ok(())
+    }
+
+    /// Undoes `grant_pool_access` for one submission. Grants the entity holds
+    /// directly or through other pools are left in place.
+    fn revoke_submission_access(&mut self, pool: &ResearchPool, submission: &PoolSubmission) -> Result<(), HealthError> {
+        let purpose = pool_purpose(&pool.pool_id);
+        self.consent_policies.remove(&consent_key(&submission.patient_id, &pool.entity_id, &purpose))?;
+
+        for record_id in &submission.record_ids {
+            let Some(mut record) = self.records.get(record_id)? else {
+                continue;
+            };
+            record.access_grants.retain(|grant| {
+                grant.provider_id != pool.entity_id || grant.pool_id.as_deref() != Some(&pool.pool_id)
+            });
+            self.records.insert(record_id.clone(), record)?;
+        }
+        Ok(())
+    }
+
+    /// Revokes what every accepted submission shared when a pool ends, and
+    /// rejects the submissions still waiting for review.
+    fn revoke_pool_access(&mut self, pool: &ResearchPool) -> Result<(), HealthError> {
+        let Some(mut submissions) = self.pool_submissions.get(&pool.pool_id)? else {
+            return Ok(());
+        };
+
+        let mut rejected = Vec::new();
+        for (patient_id, mut submission) in submissions.entries()? {
+            match submission.status {
+                SubmissionStatus::Accepted | SubmissionStatus::Rewarded => {
+                    self.revoke_submission_access(pool, &submission)?;
+                }
+                SubmissionStatus::Pending => {
+                    submission.set_status(
+                        SubmissionStatus::Rejected,
+                        &pool.entity_id,
+                        Some(format!("pool {}", pool.status))
+                    );
+                    rejected.push((patient_id, submission));
+                }
+                _ => {}
+            }
+        }
+        if rejected.is_empty() {
+            return Ok(());
+        }
+
+        for (patient_id, submission) in rejected {
+            app::emit!(HealthEvent::SubmissionUpdated {
+                pool_id: &pool.pool_id,
+                participant_id: &self.participant_id(&pool.pool_id, &patient_id),
+                entity_id: &pool.entity_id,
+                status: SubmissionStatus::Rejected
+            });
+            submissions.insert(patient_id, submission)?;
+        }
+        self.pool_submissions.insert(pool.pool_id.clone(), submissions)?;
+        Ok(())
+    }
+
+    fn participant_id(&self, pool_id: &str, patient_id: &str) -> String {
+        anonymize::pool_pseudonym(&self.pseudonym_key(), pool_id, patient_id)
+    }
+
+    /// Patient behind a participant ID, found among the pool's submissions.
+    fn resolve_participant(&self, pool_id: &str, participant_id: &str) -> Result<Option<String>, HealthError> {
+        let Some(submissions) = self.pool_submissions.get(pool_id)? else {
+            return Ok(None);
+        };
+        for (patient_id, _) in submissions.entries()? {
+            if self.participant_id(pool_id, &patient_id) == participant_id {
+                return Ok(Some(patient_id));
+            }
+        }
+        Ok(None)
+    }
+
+    /// The submission as the pool owner sees it: the patient ID, also in
+    /// the status history, becomes the participant ID.
+    fn pseudonymize_submission(&self, mut submission: PoolSubmission) -> PoolSubmission {
+        let participant_id = self.participant_id(&submission.pool_id, &submission.patient_id);
+        for change in &mut submission.history {
+            if change.changed_by == submission.patient_id {
+                change.changed_by = participant_id.clone();
+            }
+        }
+        submission.patient_id = participant_id;
+        submission
+    }
+
+    /// Key for pseudonyms and date shifts; contexts created before it existed
+    /// fall back to the context ID.
+    fn pseudonym_key(&self) -> Vec<u8> {
+        if self.pseudonym_key.is_empty() {
+            env::context_id().to_vec()
+        } else {
+            self.pseudonym_key.clone()
+        }
+    }
+
+    /// Stores a derivative of `record`, replacing any earlier one.
+    fn store_derivative(
+        &mut self,
+        mut record: HealthRecord,
+        subject_pseudonym: String,
+        data: Vec<u8>,
+        method: AnonymizationMethod,
+        proof: Option<Vec<u8>>
+    ) -> Result<AnonymizedRecord, HealthError> {
+        self.remove_derivative(&record.record_id)?;
+
+        let now = time::now();
+        let derivative = AnonymizedRecord {
+            anon_id: self.next_id("anon"),
+            subject_pseudonym,
+            record_type: record.record_type.clone(),
+            data,
+            method,
+            proof,
+            created_at: now,
+        };
+
+        self.anonymization_links.insert(record.record_id.clone(), AnonymizationLink {
+            record_id: record.record_id.clone(),
+            anon_id: derivative.anon_id.clone(),
+            patient_id: record.owner_id.clone(),
+            created_at: now,
+        })?;
+        self.anonymized_records.insert(derivative.anon_id.clone(), derivative.clone())?;
+
+        record.is_anonymized = true;
+        self.records.insert(record.record_id.clone(), record)?;
+        Ok(derivative)
+    }
+
+    fn remove_derivative(&mut self, record_id: &str) -> Result<(), HealthError> {
+        if let Some(link) = self.anonymization_links.remove(record_id)? {
+            self.anonymized_records.remove(&link.anon_id)?;
+        }
+        Ok(())
+    }
+
+    /// Submissions holding a place in the pool: pending, accepted or rewarded.
+    fn participant_count(&self, pool_id: &str) -> Result<u32, HealthError> {
+        let Some(submissions) = self.pool_submissions.get(pool_id)? else {
+            return Ok(0);
+        };
+
+        let mut count = 0;
+        for (_, submission) in submissions.entries()? {
+            if !matches!(submission.status, SubmissionStatus::Rejected | SubmissionStatus::Withdrawn) {
+                count += 1;
+            }
+        }
+        Ok(count)
+    }
+
+    /// Ledger of a pool; pools created before ledgers existed start unfunded.
+    fn pool_ledger(&self, pool_id: &str) -> Result<PoolLedger, HealthError> {
+        Ok(self.pool_ledgers.get(pool_id)?.unwrap_or_else(|| PoolLedger {
+            pool_id: pool_id.to_string(),
+            ..PoolLedger::default()
+        }))
+    }
+
+    /// Sets aside the pool's per-participant reward for an accepted submission.
+    fn reserve_reward(&mut self, pool: &ResearchPool, patient_id: &str) -> Result<(), HealthError> {
+        let key = reward_key(&pool.pool_id, patient_id);
+        if self.rewards.get(&key)?.is_some() {
+            return Err(HealthError::already_exists("reward", key));
+        }
+
+        let mut ledger = self.pool_ledger(&pool.pool_id)?;
+        if ledger.available() < pool.reward_amount {
+            return Err(HealthError::invalid_state(format!(
+                "pool {} has {} available but the reward is {}",
+                pool.pool_id, ledger.available(), pool.reward_amount
+            )));
+        }
+
+        ledger.reserved += pool.reward_amount;
+        ledger.accepted_count += 1;
+        self.pool_ledgers.insert(pool.pool_id.clone(), ledger)?;
+
+        self.rewards.insert(key, RewardEntry {
+            pool_id: pool.pool_id.clone(),
+            patient_id: patient_id.to_string(),
+            amount: pool.reward_amount,
+            status: RewardStatus::Reserved,
+            reserved_at: time::now(),
+            paid_at: None,
+            settlement_ref: None,
+        })?;
+
+        app::emit!(HealthEvent::RewardReserved {
+            pool_id: &pool.pool_id,
+            participant_id: &self.participant_id(&pool.pool_id, patient_id),
+            amount: pool.reward_amount
+        });
+
+        Ok(())
+    }
+
+    /// Returns an unclaimed reward to the pool budget.
+    fn release_reward(&mut self, pool_id: &str, patient_id: &str) -> Result<(), HealthError> {
+        let key = reward_key(pool_id, patient_id);
+        let Some(reward) = self.rewards.get(&key)? else {
+            return Ok(());
+        };
+        if reward.status != RewardStatus::Reserved {
+            return Err(HealthError::invalid_state(format!("reward {} has already been paid", key)));
+        }
+
+        let mut ledger = self.pool_ledger(pool_id)?;
+        ledger.reserved = ledger.reserved.saturating_sub(reward.amount);
+        self.pool_ledgers.insert(pool_id.to_string(), ledger)?;
+        self.rewards.remove(&key)?;
+        Ok(())
+    }
+
+    /// Stores a new record for `patient_id`; entries must already be validated.
+    fn insert_record(
+        &mut self,
+        patient_id: &str,
+        data: Vec<u8>,
+        record_type: RecordType,
+        structured: Vec<ClinicalEntry>
+    ) -> Result<String, HealthError> {
+        let record_id = self.next_id("rec");
+        let record = HealthRecord {
+            record_id: record_id.clone(),            // Stable record identifier
+            timestamp: time::now(),    // Current timestamp
+            record_type,                             // Type of medical record
+            owner_id: patient_id.to_string(),        // Patient identifier
+            access_grants: Vec::new(),               // Initially no access grants
+            is_anonymized: false,                    // Initially not anonymized
+            consent_proof: None,                     // Initially no consent proof
+            structured,                              // Coded entries, if the client sent any
+            version: 1,                              // Original contents
+            deletion: None,                          // Not deleted
+            content_hash: content_hash(&data),       // Integrity digest of the payload
+            data                                     // Store encrypted patient data
+        };
+        self.push_version(&record, patient_id, None)?;
+        self.add_record(record)?;
+        Ok(record_id)
+    }
+
+    /// Stores `record` and indexes it under its owner.
+    fn add_record(&mut self, record: HealthRecord) -> Result<(), HealthError> {
+        let patient_id = record.owner_id.clone();
+        let record_id = record.record_id.clone();
+
+        let mut patient_records = self.patient_records
+            .get(&patient_id)?
+            .unwrap_or_else(|| UnorderedMap::new());
+
+        patient_records.insert(record_id.clone(), record.timestamp)?;
+        self.patient_records.insert(patient_id.clone(), patient_records)?;
+        self.records.insert(record_id.clone(), record)?;
+
+        app::emit!(HealthEvent::RecordAdded { patient_id: &patient_id, record_id: &record_id });
+        Ok(())
+    }
+
+    /// Appends the record's current contents to its history.
+    fn push_version(&mut self, record: &HealthRecord, author_id: &str, reason: Option<String>) -> Result<(), HealthError> {
+        let mut versions = self.record_versions
+            .get(&record.record_id)?
+            .unwrap_or_default();
+
+        versions.push(RecordVersion {
+            record_id: record.record_id.clone(),
+            version: record.version,
+            data: record.data.clone(),
+            record_type: record.record_type.clone(),
+            structured: record.structured.clone(),
+            author_id: author_id.to_string(),
+            reason,
+            created_at: record.timestamp,
+            content_hash: record.content_hash.clone(),
+            imported_from: None
+        })?;
+        self.record_versions.insert(record.record_id.clone(), versions)?;
+        Ok(())
+    }
+
+    /// Versions of `record`, oldest first.
+    fn record_versions(&self, record: &HealthRecord) -> Result<Vec<RecordVersion>, HealthError> {
+        Ok(match self.record_versions.get(&record.record_id)? {
+            Some(versions) => versions.iter()?.collect(),
+            None => Vec::new(),
+        })
+    }
+
+    /// Loads `record_id` if `entity_id` may see it, auditing the attempt as
+    /// `action`; otherwise returns the denial to hand back to the caller.
+    fn readable_record<T>(
+        &mut self,
+        record_id: &str,
+        entity_id: &str,
+        action: &str
+    ) -> Result<Result<HealthRecord, AccessResult<T>>, HealthError> {
+        let Some(record) = self.live_record(record_id)? else {
+            self.authorize(entity_id, RECORD_READERS)?;
+            return Ok(Err(AccessResult::Denied(DenialReason::NotFound)));
+        };
+
+        let patient_id = record.owner_id.clone();
+        self.authorize_audited(entity_id, RECORD_READERS, action, &patient_id, Some(record_id), None)?;
+
+        let now = time::now();
+        if record.is_visible_to(entity_id, now) {
+            self.audit(action, &patient_id, Some(record_id), None, AccessOutcome::Granted)?;
+            return Ok(Ok(record));
+        }
+
+        self.audit(action, &patient_id, Some(record_id), None, AccessOutcome::Denied)?;
+        Ok(Err(self.deny(&patient_id, record.denial_reason(entity_id, now))?))
+    }
+
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.id_nonce += 1;
+        format!("{}-{:x}-{}", prefix, time::now(), self.id_nonce)
+    }
+
+    /// Loads every record belonging to `patient_id`, most recent first.
+    fn patient_records_newest_first(&self, patient_id: &str) -> Result<Vec<HealthRecord>, HealthError> {
+        let Some(index) = self.patient_records.get(patient_id)? else {
+            return Ok(Vec::new());
+        };
+
+        let mut records = Vec::new();
+        for (record_id, _) in index.entries()? {
+            if let Some(record) = self.live_record(&record_id)? {
+                records.push(record);
+            }
+        }
+
+        records.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+        Ok(records)
+    }
+
+    /// Record `record_id`, unless it is missing or soft-deleted.
+    fn live_record(&self, record_id: &str) -> Result<Option<HealthRecord>, HealthError> {
+        Ok(self.records.get(record_id)?.filter(|record| record.deletion.is_none()))
+    }
+
+    /// Marks `record` deleted; it stays restorable for the current retention period.
+    fn soft_delete(&mut self, mut record: HealthRecord, deleted_by: &str) -> Result<(), HealthError> {
+        let now = time::now();
+        let purge_after = now.saturating_add(time::secs_to_nanos(self.retention_secs));
+        record.deletion = Some(Deletion {
+            deleted_at: now,
+            deleted_by: deleted_by.to_string(),
+            purge_after
+        });
+
+        let patient_id = record.owner_id.clone();
+        let record_id = record.record_id.clone();
+        self.records.insert(record_id.clone(), record)?;
+
+        app::emit!(HealthEvent::RecordDeleted { patient_id: &patient_id, record_id: &record_id, purge_after });
+        Ok(())
+    }
+
+    /// Permanently removes `record` with its versions and derivative.
+    fn remove_record(&mut self, record: &HealthRecord) -> Result<(), HealthError> {
+        self.records.remove(&record.record_id)?;
+        self.record_versions.remove(&record.record_id)?;
+        self.integrity_anchors.remove(&record.record_id)?;
+        self.remove_derivative(&record.record_id)?;
+
+        if let Some(mut index) = self.patient_records.get(&record.owner_id)? {
+            index.remove(&record.record_id)?;
+            if index.len()? == 0 {
+                self.patient_records.remove(&record.owner_id)?;
+            } else {
+                self.patient_records.insert(record.owner_id.clone(), index)?;
+            }
+        }
+        Ok(())
+    }
+}
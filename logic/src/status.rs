@@ -0,0 +1,153 @@
+//! Lifecycle states for research pools and pool submissions.
+//!
+//! Both enums are stored as their snake_case name rather than a Borsh enum
+//! tag, so adding a state never renumbers the ones already stored. A name
+//! that is not one of the states is a decode error, not a guess.
+//!
+//! `ResearchPool` and `PoolSubmission` gained fields when these types were
+//! introduced, so state written by earlier versions does not decode and
+//! those contexts need to be re-initialised.
+
+use std::fmt;
+use std::io;
+
+use calimero_sdk::borsh::{BorshDeserialize, BorshSerialize};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum PoolStatus {
+    Draft,
+    #[default]
+    Active,
+    Paused,
+    Closed,
+    Cancelled,
+}
+
+impl PoolStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PoolStatus::Draft => "draft",
+            PoolStatus::Active => "active",
+            PoolStatus::Paused => "paused",
+            PoolStatus::Closed => "closed",
+            PoolStatus::Cancelled => "cancelled",
+        }
+    }
+
+    fn from_name(value: &str) -> Option<Self> {
+        match value {
+            "draft" => Some(PoolStatus::Draft),
+            "active" => Some(PoolStatus::Active),
+            "paused" => Some(PoolStatus::Paused),
+            "closed" => Some(PoolStatus::Closed),
+            "cancelled" => Some(PoolStatus::Cancelled),
+            _ => None,
+        }
+    }
+
+    pub fn is_terminal(self) -> bool {
+        matches!(self, PoolStatus::Closed | PoolStatus::Cancelled)
+    }
+
+    pub fn can_transition_to(self, next: PoolStatus) -> bool {
+        use PoolStatus::*;
+        matches!(
+            (self, next),
+            (Draft, Active)
+                | (Draft, Cancelled)
+                | (Active, Paused)
+                | (Active, Closed)
+                | (Active, Cancelled)
+                | (Paused, Active)
+                | (Paused, Closed)
+                | (Paused, Cancelled)
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum SubmissionStatus {
+    #[default]
+    Pending,
+    Accepted,
+    Rejected,
+    Withdrawn,
+    Rewarded,
+}
+
+impl SubmissionStatus {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SubmissionStatus::Pending => "pending",
+            SubmissionStatus::Accepted => "accepted",
+            SubmissionStatus::Rejected => "rejected",
+            SubmissionStatus::Withdrawn => "withdrawn",
+            SubmissionStatus::Rewarded => "rewarded",
+        }
+    }
+
+    fn from_name(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(SubmissionStatus::Pending),
+            "accepted" => Some(SubmissionStatus::Accepted),
+            "rejected" => Some(SubmissionStatus::Rejected),
+            "withdrawn" => Some(SubmissionStatus::Withdrawn),
+            "rewarded" => Some(SubmissionStatus::Rewarded),
+            _ => None,
+        }
+    }
+
+    /// Whether the submission is finished and may be replaced by a new one.
+    pub fn is_terminal(self) -> bool {
+        matches!(
+            self,
+            SubmissionStatus::Rejected | SubmissionStatus::Withdrawn | SubmissionStatus::Rewarded
+        )
+    }
+
+    pub fn can_transition_to(self, next: SubmissionStatus) -> bool {
+        use SubmissionStatus::*;
+        matches!(
+            (self, next),
+            (Pending, Accepted)
+                | (Pending, Rejected)
+                | (Pending, Withdrawn)
+                | (Accepted, Rewarded)
+                | (Accepted, Withdrawn)
+        )
+    }
+}
+
+macro_rules! stored_as_name {
+    ($ty:ty) => {
+        impl fmt::Display for $ty {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl BorshSerialize for $ty {
+            fn serialize<W: io::Write>(&self, writer: &mut W) -> io::Result<()> {
+                BorshSerialize::serialize(self.as_str(), writer)
+            }
+        }
+
+        impl BorshDeserialize for $ty {
+            fn deserialize_reader<R: io::Read>(reader: &mut R) -> io::Result<Self> {
+                let value = String::deserialize_reader(reader)?;
+                Self::from_name(&value).ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unknown {} {:?}", stringify!($ty), value),
+                    )
+                })
+            }
+        }
+    };
+}
+
+stored_as_name!(PoolStatus);
+stored_as_name!(SubmissionStatus);
@@ -0,0 +1,87 @@
+//! Grants and consents: what they give access to and how they are revoked.
+
+use super::*;
+
+#[test]
+fn grant_access_needs_records_to_grant() {
+    let mut store = setup();
+    act_as(ALICE);
+    let expires_at = secs_from_now(time::SECS_PER_DAY);
+    assert_eq!(code(store.grant_access(ALICE.into(), HOSPITAL.into(), expires_at, None)), "NOT_FOUND");
+
+    let record_id = add_record(&mut store, ALICE);
+    act_as(ALICE);
+    store.grant_access(ALICE.into(), HOSPITAL.into(), expires_at, None).unwrap();
+    let grants = store.list_access_grants(ALICE, false).unwrap();
+    assert_eq!(grants.len(), 1);
+    assert_eq!(grants[0].record_id, record_id);
+}
+
+#[test]
+fn identity_ids_cannot_carry_the_key_separator() {
+    let mut store = setup();
+    env::set_executor(key(STRANGER_KEY));
+    let too_long = "a".repeat(65);
+    assert_codes!("INVALID_INPUT":
+        store.register_identity("alice:treatment".into(), IdentityKind::Patient, "Alice".into());
+        store.register_identity("hospital/x".into(), IdentityKind::Provider, "Hospital".into());
+        store.register_identity(" ".into(), IdentityKind::Patient, "Blank".into());
+        store.register_identity(too_long.clone(), IdentityKind::Patient, "Long".into());
+    );
+    assert!(store.whoami().unwrap().is_none());
+
+    store.register_identity("carol.d_2-x".into(), IdentityKind::Patient, "Carol".into()).unwrap();
+}
+
+#[test]
+fn consents_only_serve_reads_for_their_purpose() {
+    let mut store = setup();
+    let record_id = add_record(&mut store, ALICE);
+    act_as(ALICE);
+    store.add_consent(ALICE.into(), HOSPITAL.into(), "billing".into(), time::SECS_PER_DAY, String::new()).unwrap();
+
+    act_as(HOSPITAL);
+    assert!(matches!(store.access_patient_data(ALICE, HOSPITAL, "billing").unwrap(), AccessResult::Granted(_)));
+    assert!(matches!(
+        store.access_patient_data(ALICE, HOSPITAL, "treatment").unwrap(),
+        AccessResult::Denied(DenialReason::ConsentMissing)
+    ));
+    assert!(matches!(
+        store.get_patient_data(ALICE, HOSPITAL).unwrap(),
+        AccessResult::Denied(DenialReason::NotAuthorized)
+    ));
+    assert!(matches!(
+        store.get_record(&record_id, HOSPITAL).unwrap(),
+        AccessResult::Denied(DenialReason::NotAuthorized)
+    ));
+    assert!(store.list_authorized_reports(HOSPITAL).unwrap().is_empty());
+
+    // Research purposes belong to pool acceptances and never serve the record
+    act_as(ALICE);
+    assert_eq!(
+        code(store.add_consent(ALICE.into(), LAB.into(), "Research:pool-1".into(), time::SECS_PER_DAY, String::new())),
+        "INVALID_INPUT"
+    );
+}
+
+#[test]
+fn revoke_consent_drops_a_single_purpose() {
+    let mut store = setup();
+    add_record(&mut store, ALICE);
+    act_as(ALICE);
+    for purpose in ["billing", "treatment"] {
+        store.add_consent(ALICE.into(), HOSPITAL.into(), purpose.into(), time::SECS_PER_DAY, String::new()).unwrap();
+    }
+    store.grant_access(ALICE.into(), HOSPITAL.into(), secs_from_now(time::SECS_PER_DAY), None).unwrap();
+
+    store.revoke_consent(ALICE, HOSPITAL, " Billing ").unwrap();
+    assert_eq!(code(store.verify_consent(ALICE, HOSPITAL, "billing")), "CONSENT_MISSING");
+    store.verify_consent(ALICE, HOSPITAL, "treatment").unwrap();
+    assert_eq!(store.list_access_grants(ALICE, false).unwrap().len(), 1);
+
+    assert_codes!("NOT_FOUND":
+        store.revoke_consent(ALICE, HOSPITAL, "billing");
+        store.revoke_consent(ALICE, CLINIC, "treatment");
+    );
+    assert_eq!(code(store.revoke_consent(ALICE, LAB, "research:pool-1")), "INVALID_INPUT");
+}
@@ -0,0 +1,252 @@
+//! Every `#[app::logic]` method either acts on the identity bound to the
+//! executor key, or checks that the key is bound to the identity the call
+//! claims to act for. These tests call each method with someone else's
+//! identity, or someone else's record or pool.
+//!
+//! `init`, `whoami`, `get_identity`, `get_retention_period` and
+//! `list_research_pools` take no identity and are open to every caller.
+
+use super::*;
+
+struct Fixture {
+    record_id: String,
+    deleted_id: String,
+    pool_id: String,
+    participant_id: String,
+    bundle: PatientBundle,
+}
+
+/// Alice with a live and a deleted record, accepted into a pool run by `lab`.
+fn fixture(store: &mut HealthDataStore) -> Fixture {
+    let record_id = add_record(store, ALICE);
+    let deleted_id = add_record(store, ALICE);
+    store.delete_record(&deleted_id).unwrap();
+
+    let pool_id = add_pool(store, LAB);
+    act_as(ALICE);
+    store.submit_to_pool(&pool_id, ALICE, vec![record_id.clone()]).unwrap();
+    let participant_id = store.get_participant_id(&pool_id, ALICE).unwrap();
+    let bundle = store.export_patient_bundle(ALICE).unwrap();
+
+    act_as(LAB);
+    store.update_submission_status(&pool_id, &participant_id, SubmissionStatus::Accepted, None).unwrap();
+
+    Fixture { record_id, deleted_id, pool_id, participant_id, bundle }
+}
+
+#[test]
+fn patient_entry_points_reject_another_patient() {
+    let mut store = setup();
+    let f = fixture(&mut store);
+    let rec = f.record_id.as_str();
+    let pool = f.pool_id.as_str();
+
+    act_as(BOB);
+    assert_codes!("UNAUTHORIZED":
+        store.store_patient_data(ALICE.into(), b"forged".to_vec(), None, RecordType::Document, None);
+        store.import_fhir(ALICE.into(), serde_json::json!({ "resourceType": "Observation" }));
+        store.get_patient_records(ALICE);
+        store.update_record(rec, b"forged".to_vec(), RecordType::Document, None, None);
+        store.update_patient_data(ALICE, b"forged".to_vec(), RecordType::Document, None, None);
+        store.anchor_record_hash(rec, "0x1".into(), "0x2".into());
+        store.delete_record(rec);
+        store.restore_record(&f.deleted_id);
+        store.list_deleted_records(ALICE);
+        store.delete_patient_data(ALICE);
+        store.add_consent(ALICE.into(), BOB.into(), "treatment".into(), time::SECS_PER_DAY, String::new());
+        store.set_privacy_settings(ALICE, true);
+        store.get_privacy_settings(ALICE);
+        store.anonymize_record(rec);
+        store.register_anonymized_record(rec, b"anonymized".to_vec(), Vec::new());
+        store.get_anonymization_links(ALICE);
+        store.revoke_access(ALICE, LAB);
+        store.revoke_consent(ALICE, LAB, "treatment");
+        store.grant_access(ALICE.into(), BOB.into(), secs_from_now(time::SECS_PER_DAY), None);
+        store.list_access_grants(ALICE, true);
+        store.export_patient_bundle(ALICE);
+        store.import_patient_bundle(ALICE.into(), f.bundle.clone());
+        store.submit_to_pool(pool, ALICE, vec![rec.to_string()]);
+        store.withdraw_submission(pool, ALICE, None);
+        store.get_patient_submissions(ALICE);
+        store.get_participant_id(pool, ALICE);
+        store.claim_reward(pool, ALICE, None);
+        store.get_reward_balance(ALICE);
+    );
+
+    // Nothing the rejected calls attempted took effect
+    act_as(ALICE);
+    let records = store.get_patient_records(ALICE).unwrap();
+    assert_eq!(records.len(), 1);
+    assert_eq!(records[0].data, store.records.get(rec).unwrap().unwrap().data);
+    assert_eq!(store.list_deleted_records(ALICE).unwrap().len(), 1);
+    assert_eq!(store.get_reward_balance(ALICE).unwrap().claimable, 10);
+}
+
+#[test]
+fn reader_entry_points_reject_another_entity() {
+    let mut store = setup();
+    let f = fixture(&mut store);
+    let rec = f.record_id.as_str();
+
+    act_as(ALICE);
+    store.grant_access(ALICE.into(), HOSPITAL.into(), secs_from_now(time::SECS_PER_DAY), None).unwrap();
+    store.add_consent(ALICE.into(), HOSPITAL.into(), "treatment".into(), time::SECS_PER_DAY, String::new()).unwrap();
+
+    act_as(CLINIC);
+    assert_codes!("UNAUTHORIZED":
+        store.get_record(rec, HOSPITAL);
+        store.get_record("rec-missing", HOSPITAL);
+        store.list_patient_records(ALICE, HOSPITAL, None, None, None, None, None, None);
+        store.get_patient_data(ALICE, HOSPITAL);
+        store.get_record_history(rec, HOSPITAL);
+        store.get_record_version(rec, HOSPITAL, 1);
+        store.verify_record_integrity(rec, HOSPITAL);
+        store.export_fhir_bundle(ALICE, HOSPITAL);
+        store.access_patient_data(ALICE, HOSPITAL, "treatment");
+        store.verify_consent(ALICE, HOSPITAL, "treatment");
+        store.list_authorized_reports(HOSPITAL);
+    );
+
+    // A patient cannot read as the provider either
+    act_as(BOB);
+    assert_codes!("UNAUTHORIZED":
+        store.get_record(rec, HOSPITAL);
+        store.get_record(rec, ALICE);
+    );
+}
+
+#[test]
+fn researcher_entry_points_reject_another_researcher() {
+    let mut store = setup();
+    let f = fixture(&mut store);
+    let pool = f.pool_id.as_str();
+
+    act_as(RIVAL_LAB);
+    assert_codes!("UNAUTHORIZED":
+        store.create_research_pool(LAB.into(), "Forged".into(), String::new(), 1, secs_from_now(time::SECS_PER_DAY), None, None, None);
+        store.update_research_pool(pool, Some("Forged".into()), None, None, None, None, None);
+        store.delete_research_pool(pool);
+        store.update_submission_status(pool, &f.participant_id, SubmissionStatus::Rejected, None);
+        store.get_pool_submissions(pool, None, None, None);
+        store.fund_pool(pool, 5, None);
+        store.get_pool_ledger(pool);
+        store.get_pool_anonymized_data(pool, &f.participant_id);
+    );
+
+    act_as(LAB);
+    assert_eq!(store.get_research_pool(pool).unwrap().unwrap().title, "Cardiology");
+    assert_eq!(store.get_pool_ledger(pool).unwrap().funded, 100);
+}
+
+#[test]
+fn privileged_entry_points_require_their_role() {
+    let mut store = setup();
+    let f = fixture(&mut store);
+
+    act_as(BOB);
+    assert_codes!("MISSING_ROLE":
+        store.assign_role(BOB.into(), Role::Admin);
+        store.revoke_role(ADMIN.into(), Role::Admin);
+        store.get_roles(ALICE);
+        store.get_access_log(ALICE, None, None);
+        store.get_entity_access_log(HOSPITAL, None, None);
+        store.set_retention_period(time::SECS_PER_DAY);
+        store.place_legal_hold(ALICE.into(), "litigation".into());
+        store.release_legal_hold(ALICE);
+        store.get_legal_hold(ALICE);
+        store.purge_deleted_records(None);
+        store.forget_patient(ALICE);
+        store.get_erasure_receipt("erasure-1");
+        store.reidentify_participant(&f.pool_id, &f.participant_id);
+    );
+
+    // Entities cannot read their own log: it names the patients behind participants
+    for entity in [LAB, HOSPITAL] {
+        act_as(entity);
+        assert_eq!(code(store.get_entity_access_log(entity, None, None)), "MISSING_ROLE");
+    }
+    act_as(AUDITOR);
+    assert_eq!(code(store.get_entity_access_log(LAB, None, None)), "OK");
+}
+
+#[test]
+fn key_bound_entry_points_only_act_on_the_callers_identity() {
+    let mut store = setup();
+    let alice_key = bs58::encode(key_of(ALICE)).into_string();
+
+    act_as(BOB);
+    assert_codes!("ALREADY_EXISTS":
+        store.register_identity("bob-2".into(), IdentityKind::Patient, "Bob".into());
+        store.add_identity_key(alice_key.clone());
+        store.rotate_identity_key(alice_key.clone());
+    );
+    assert_eq!(code(store.remove_identity_key(alice_key.clone())), "NOT_FOUND");
+    assert_eq!(store.whoami().unwrap().unwrap().identity_id, BOB);
+    assert_eq!(store.get_my_roles().unwrap(), vec![Role::Patient]);
+
+    // A fresh key cannot take over an existing identity
+    env::set_executor(key(STRANGER_KEY));
+    assert_eq!(code(store.register_identity(ALICE.into(), IdentityKind::Patient, "Alice".into())), "ALREADY_EXISTS");
+
+    act_as(ALICE);
+    assert_eq!(store.whoami().unwrap().unwrap().keys, vec![alice_key]);
+}
+
+#[test]
+fn unregistered_keys_are_rejected() {
+    let mut store = setup();
+    let record_id = add_record(&mut store, ALICE);
+
+    env::set_executor(key(STRANGER_KEY));
+    assert_codes!("UNREGISTERED_KEY":
+        store.store_patient_data(ALICE.into(), b"forged".to_vec(), None, RecordType::Document, None);
+        store.get_record(&record_id, ALICE);
+        store.get_my_roles();
+        store.add_identity_key(key_string(0xef));
+    );
+    assert!(store.whoami().unwrap().is_none());
+}
+
+#[test]
+fn rejected_reads_are_audited() {
+    let mut store = setup();
+    let record_id = add_record(&mut store, ALICE);
+
+    act_as(CLINIC);
+    assert_eq!(code(store.get_record(&record_id, HOSPITAL)), "UNAUTHORIZED");
+
+    act_as(ALICE);
+    let log = store.get_access_log(ALICE, None, None).unwrap();
+    let entry = &log.items[0];
+    assert_eq!(entry.accessor_id, CLINIC);
+    assert_eq!(entry.method, "get_record");
+    assert_eq!(entry.outcome, AccessOutcome::Denied);
+}
+
+#[test]
+fn draft_pools_are_only_visible_to_their_owner() {
+    let mut store = setup();
+    act_as(LAB);
+    let draft_id = store
+        .create_research_pool(LAB.into(), "Draft".into(), String::new(), 1, secs_from_now(time::SECS_PER_DAY), Some(true), None, None)
+        .unwrap();
+    let active_id = add_pool(&mut store, LAB);
+
+    act_as(LAB);
+    assert!(store.get_research_pool(&draft_id).unwrap().is_some());
+    assert_eq!(store.list_entity_pools(LAB).unwrap().len(), 2);
+
+    for viewer in [RIVAL_LAB, ALICE, HOSPITAL] {
+        act_as(viewer);
+        assert!(store.get_research_pool(&draft_id).unwrap().is_none(), "{} sees the draft", viewer);
+        assert!(store.get_research_pool(&active_id).unwrap().is_some());
+        let listed: Vec<String> = store.list_entity_pools(LAB).unwrap().into_iter().map(|pool| pool.pool_id).collect();
+        assert_eq!(listed, vec![active_id.clone()]);
+    }
+
+    // Identities without a reading role, and unregistered keys, see nothing
+    act_as(AUDITOR);
+    assert_eq!(code(store.list_entity_pools(LAB)), "MISSING_ROLE");
+    env::set_executor(key(STRANGER_KEY));
+    assert_eq!(code(store.get_research_pool(&active_id)), "UNREGISTERED_KEY");
+}
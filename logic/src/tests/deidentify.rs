@@ -0,0 +1,69 @@
+//! The payload rewriting behind anonymized derivatives: which fields go,
+//! which are pseudonymized, and how dates move.
+
+use super::*;
+
+fn deidentify(value: serde_json::Value, shift_days: i64) -> serde_json::Value {
+    let data = serde_json::to_vec(&value).unwrap();
+    serde_json::from_slice(&anonymize::deidentify_json(&data, "subj-1", shift_days).unwrap()).unwrap()
+}
+
+#[test]
+fn identifiers_are_dropped_and_subjects_pseudonymized() {
+    let scrubbed = deidentify(serde_json::json!({
+        "patient_id": "alice",
+        "Full-Name": "Alice Liddell",
+        "contact": { "email": "alice@example.org", "phone": "555-0100" },
+        "MRN": "12345",
+        "visits": [{ "subjectId": "alice", "note": "follow-up", "Address": "1 Rabbit Hole" }],
+        "heart_rate": 62
+    }), 10);
+
+    assert_eq!(scrubbed, serde_json::json!({
+        "patient_id": "subj-1",
+        "visits": [{ "subjectId": "subj-1", "note": "follow-up" }],
+        "heart_rate": 62
+    }));
+}
+
+#[test]
+fn dates_shift_by_whole_days_and_birth_dates_keep_the_year() {
+    let scrubbed = deidentify(serde_json::json!({
+        "effective": "2024-02-25T08:30:00Z",
+        "issued": "2024-12-31",
+        "birthDate": "1990-12-30",
+        "dob": "not a date",
+        "recorded": "2024-02-25 23:59",
+        "note": "2024-02-25, a Sunday",
+        "code": "2024-13-01"
+    }), 5);
+
+    assert_eq!(scrubbed["effective"], "2024-03-01T08:30:00Z");
+    assert_eq!(scrubbed["issued"], "2025-01-05");
+    assert_eq!(scrubbed["birthDate"], "1991");
+    assert_eq!(scrubbed["dob"], serde_json::Value::Null);
+    // Only strings that are a date, optionally followed by a time, move
+    assert_eq!(scrubbed["recorded"], "2024-03-01 23:59");
+    assert_eq!(scrubbed["note"], "2024-02-25, a Sunday");
+    assert_eq!(scrubbed["code"], "2024-13-01");
+
+    let back = deidentify(serde_json::json!({ "effective": "2024-03-01T08:30:00Z" }), -5);
+    assert_eq!(back["effective"], "2024-02-25T08:30:00Z");
+}
+
+#[test]
+fn encrypted_payloads_are_left_to_the_client() {
+    assert!(anonymize::deidentify_json(b"\x8a\x01ciphertext", "subj-1", 10).is_none());
+    assert!(anonymize::deidentify_json(b"", "subj-1", 10).is_none());
+}
+
+#[test]
+fn date_shifts_are_stable_per_subject_and_bounded() {
+    let key = b"context-key";
+    let shifts: Vec<i64> = (0..200).map(|i| anonymize::date_shift_days(key, &format!("patient-{i}"))).collect();
+
+    assert!(shifts.iter().all(|&shift| shift != 0 && (-365..=365).contains(&shift)));
+    assert!(shifts.iter().any(|&shift| shift < 0) && shifts.iter().any(|&shift| shift > 0));
+    assert_eq!(anonymize::date_shift_days(key, "patient-7"), shifts[7]);
+    assert_ne!(anonymize::date_shift_days(b"other-key", "patient-7"), shifts[7]);
+}
@@ -0,0 +1,138 @@
+//! `forget_patient`: what it erases, what it keeps under a tombstone, and
+//! when it refuses.
+
+use super::*;
+
+struct Fixture {
+    pool_id: String,
+    claimed_pool_id: String,
+}
+
+/// Alice with a live, an updated, a deleted and an anonymized record, a
+/// consent for the hospital, and two accepted pool submissions, one of them
+/// already paid out.
+fn fixture(store: &mut HealthDataStore) -> Fixture {
+    let record_id = add_record(store, ALICE);
+    let updated_id = add_record(store, ALICE);
+    let deleted_id = add_record(store, ALICE);
+    add_record(store, BOB);
+
+    act_as(ALICE);
+    store.update_record(&updated_id, b"revised".to_vec(), RecordType::Document, None, None).unwrap();
+    store.delete_record(&deleted_id).unwrap();
+    store.anonymize_record(&record_id).unwrap();
+    store.set_privacy_settings(ALICE, true).unwrap();
+    store.add_consent(ALICE.into(), HOSPITAL.into(), "treatment".into(), time::SECS_PER_DAY, String::new()).unwrap();
+
+    act_as(HOSPITAL);
+    store.get_record(&record_id, HOSPITAL).unwrap();
+
+    let pool_id = add_pool(store, LAB);
+    let claimed_pool_id = add_pool(store, LAB);
+    for pool in [&pool_id, &claimed_pool_id] {
+        act_as(ALICE);
+        store.submit_to_pool(pool, ALICE, vec![record_id.clone()]).unwrap();
+        let participant_id = store.get_participant_id(pool, ALICE).unwrap();
+        act_as(LAB);
+        store.update_submission_status(pool, &participant_id, SubmissionStatus::Accepted, None).unwrap();
+    }
+    act_as(ALICE);
+    store.claim_reward(&claimed_pool_id, ALICE, None).unwrap();
+
+    Fixture { pool_id, claimed_pool_id }
+}
+
+#[test]
+fn receipt_counts_what_was_erased() {
+    let mut store = setup();
+    fixture(&mut store);
+
+    act_as(ALICE);
+    let receipt = store.forget_patient(ALICE).unwrap();
+    assert_eq!(receipt.records, 3);
+    assert_eq!(receipt.record_versions, 4);
+    assert_eq!(receipt.anonymized_records, 1);
+    // The treatment consent and one per accepted pool submission
+    assert_eq!(receipt.consents, 3);
+    assert_eq!(receipt.pool_submissions, 2);
+    assert_eq!(receipt.rewards_released, 1);
+    assert_eq!(receipt.rewards_tombstoned, 1);
+    assert!(receipt.access_log_entries > 0);
+    assert_eq!(receipt.identity_keys, 1);
+    assert_eq!(receipt.requested_by, receipt.subject_tombstone);
+
+    act_as(ADMIN);
+    let stored = store.get_erasure_receipt(&receipt.receipt_id).unwrap();
+    assert_eq!(stored.records, 3);
+}
+
+#[test]
+fn every_collection_forgets_the_patient() {
+    let mut store = setup();
+    let f = fixture(&mut store);
+
+    act_as(ADMIN);
+    let receipt = store.forget_patient(ALICE).unwrap();
+    let tombstone = receipt.subject_tombstone;
+
+    let owned_by_alice = |owner: &str| owner == ALICE;
+    assert!(!store.records.entries().unwrap().any(|(_, record)| owned_by_alice(&record.owner_id)));
+    assert!(store.patient_records.get(ALICE).unwrap().is_none());
+    assert_eq!(store.record_versions.len().unwrap(), 1);
+    assert_eq!(store.anonymized_records.len().unwrap(), 0);
+    assert_eq!(store.anonymization_links.len().unwrap(), 0);
+    assert!(!store.consent_policies.entries().unwrap().any(|(_, consent)| owned_by_alice(&consent.patient_id)));
+    for pool in [&f.pool_id, &f.claimed_pool_id] {
+        assert!(store.pool_submissions.get(pool).unwrap().unwrap().get(ALICE).unwrap().is_none());
+    }
+    assert!(store.privacy_settings.get(ALICE).unwrap().is_none());
+
+    // The paid reward stays on the books under the tombstone; the reserved one went back
+    let rewards: Vec<RewardEntry> = store.rewards.entries().unwrap().map(|(_, reward)| reward).collect();
+    assert_eq!(rewards.len(), 1);
+    assert_eq!(rewards[0].patient_id, tombstone);
+    assert_eq!(store.pool_ledger(&f.pool_id).unwrap().reserved, 0);
+
+    let log: Vec<AccessLogEntry> = store.access_log.iter().unwrap().collect();
+    assert!(!log.is_empty());
+    assert!(log.iter().all(|entry| entry.patient_id != ALICE && entry.accessor_id != ALICE));
+
+    // The identity, its key and its roles are gone
+    assert!(store.identities.get(ALICE).unwrap().is_none());
+    assert!(store.roles.get(ALICE).unwrap().is_none());
+    assert!(store.get_identity(ALICE).unwrap().is_none());
+    act_as(ALICE);
+    assert!(store.whoami().unwrap().is_none());
+    assert_eq!(code(store.get_patient_records(ALICE)), "UNREGISTERED_KEY");
+}
+
+#[test]
+fn legal_hold_blocks_erasure() {
+    let mut store = setup();
+    fixture(&mut store);
+
+    act_as(ADMIN);
+    store.place_legal_hold(ALICE.into(), "litigation".into()).unwrap();
+    assert_eq!(code(store.forget_patient(ALICE)), "INVALID_STATE");
+    act_as(ALICE);
+    assert_eq!(code(store.forget_patient(ALICE)), "INVALID_STATE");
+
+    assert_eq!(store.get_patient_records(ALICE).unwrap().len(), 2);
+    assert!(store.whoami().unwrap().is_some());
+
+    act_as(ADMIN);
+    store.release_legal_hold(ALICE).unwrap();
+    assert_eq!(code(store.forget_patient(ALICE)), "OK");
+}
+
+#[test]
+fn the_last_admin_cannot_be_erased() {
+    let mut store = setup();
+    act_as(ADMIN);
+    store.assign_role(ALICE.into(), Role::Admin).unwrap();
+    store.revoke_role(ADMIN.into(), Role::Admin).unwrap();
+
+    act_as(ALICE);
+    assert_eq!(code(store.forget_patient(ALICE)), "INVALID_STATE");
+    assert!(store.whoami().unwrap().is_some());
+}
@@ -0,0 +1,104 @@
+//! One test per `HealthError` code, each triggered the way a client would
+//! hit it, plus the `{ code, message }` shape clients match on.
+
+use super::*;
+
+#[test]
+fn serializes_as_code_and_message() {
+    let err = HealthError::not_found("record", "rec-1");
+    assert_eq!(
+        serde_json::to_value(&err).unwrap(),
+        serde_json::json!({ "code": "NOT_FOUND", "message": "record rec-1 not found" })
+    );
+}
+
+#[test]
+fn not_found() {
+    let mut store = setup();
+    act_as(ALICE);
+    assert_eq!(code(store.update_record("rec-missing", b"x".to_vec(), RecordType::Document, None, None)), "NOT_FOUND");
+}
+
+#[test]
+fn already_exists() {
+    let mut store = setup();
+    env::set_executor(key(STRANGER_KEY));
+    assert_eq!(code(store.register_identity(ALICE.into(), IdentityKind::Patient, "Alice".into())), "ALREADY_EXISTS");
+}
+
+#[test]
+fn expired() {
+    let mut store = setup();
+    act_as(ALICE);
+    store.add_consent(ALICE.into(), HOSPITAL.into(), "treatment".into(), time::SECS_PER_DAY, String::new()).unwrap();
+    advance_secs(time::SECS_PER_DAY + 1);
+
+    act_as(HOSPITAL);
+    assert_eq!(code(store.verify_consent(ALICE, HOSPITAL, "treatment")), "EXPIRED");
+}
+
+#[test]
+fn invalid_input() {
+    let mut store = setup();
+    act_as(ALICE);
+    assert_eq!(code(store.add_consent(ALICE.into(), HOSPITAL.into(), "  ".into(), time::SECS_PER_DAY, String::new())), "INVALID_INPUT");
+}
+
+#[test]
+fn invalid_state() {
+    let mut store = setup();
+    act_as(ADMIN);
+    assert_eq!(code(store.revoke_role(ADMIN.into(), Role::Admin)), "INVALID_STATE");
+}
+
+#[test]
+fn unauthorized() {
+    let store = setup();
+    act_as(BOB);
+    assert_eq!(code(store.get_patient_records(ALICE)), "UNAUTHORIZED");
+}
+
+#[test]
+fn missing_role() {
+    let mut store = setup();
+    act_as(HOSPITAL);
+    assert_eq!(code(store.assign_role(HOSPITAL.into(), Role::Admin)), "MISSING_ROLE");
+}
+
+#[test]
+fn unregistered_key() {
+    let store = setup();
+    env::set_executor(key(STRANGER_KEY));
+    assert_eq!(code(store.get_my_roles()), "UNREGISTERED_KEY");
+}
+
+#[test]
+fn invalid_key() {
+    let mut store = setup();
+    act_as(ALICE);
+    assert_eq!(code(store.add_identity_key("not-a-key".into())), "INVALID_KEY");
+}
+
+#[test]
+fn consent_missing() {
+    let store = setup();
+    act_as(HOSPITAL);
+    assert_eq!(code(store.verify_consent(ALICE, HOSPITAL, "treatment")), "CONSENT_MISSING");
+}
+
+#[test]
+fn not_eligible() {
+    let mut store = setup();
+    let alice_record = add_record(&mut store, ALICE);
+    let bob_record = add_record(&mut store, BOB);
+
+    act_as(LAB);
+    let pool_id = store
+        .create_research_pool(LAB.into(), "Small".into(), String::new(), 1, secs_from_now(time::SECS_PER_DAY), None, Some(1), None)
+        .unwrap();
+
+    act_as(ALICE);
+    store.submit_to_pool(&pool_id, ALICE, vec![alice_record]).unwrap();
+    act_as(BOB);
+    assert_eq!(code(store.submit_to_pool(&pool_id, BOB, vec![bob_record])), "NOT_ELIGIBLE");
+}
@@ -0,0 +1,86 @@
+//! Content hashes, integrity checks and the Starknet anchors clients verify.
+
+use super::*;
+
+#[test]
+fn integrity_reports_the_key_to_check_on_starknet() {
+    let mut store = setup();
+    let record_id = add_record(&mut store, ALICE);
+    act_as(ALICE);
+    assert_codes!("INVALID_INPUT":
+        store.anchor_record_hash(&record_id, "not-a-hash".into(), "0xabc".into());
+        store.anchor_record_hash(&record_id, "0x1".into(), "alice".into());
+    );
+    let anchor = store.anchor_record_hash(&record_id, "0xBEEF".into(), " 0xABC ".into()).unwrap();
+    assert_eq!((anchor.tx_hash.as_str(), anchor.account.as_str()), ("0xbeef", "0xabc"));
+
+    let AccessResult::Granted(report) = store.verify_record_integrity(&record_id, ALICE).unwrap() else {
+        panic!("the owner cannot verify their record");
+    };
+    let key = report.chain_key;
+    let context = bs58::encode(env::context_id()).into_string();
+    assert_eq!(key.context_id, starknet_felt(&context));
+    assert_eq!(key.record_id, starknet_felt(&record_id));
+    assert_eq!(key.version, 1);
+    assert_eq!(key.content_hash, format!("0x{}", report.computed_hash));
+    // 31 bytes: always below the felt252 prime
+    for felt in [&key.context_id, &key.record_id] {
+        assert_eq!(felt.len(), 2 + 62, "{felt}");
+    }
+    assert_ne!(key.context_id, key.record_id);
+}
+
+#[test]
+fn integrity_flags_tampered_payloads_and_versions() {
+    let mut store = setup();
+    let record_id = add_record(&mut store, ALICE);
+    act_as(ALICE);
+    store.update_record(&record_id, b"second".to_vec(), RecordType::Observation, None, None).unwrap();
+    let report = |store: &mut HealthDataStore| match store.verify_record_integrity(&record_id, ALICE).unwrap() {
+        AccessResult::Granted(report) => report,
+        _ => panic!("the owner cannot verify their record"),
+    };
+
+    let clean = report(&mut store);
+    assert!(clean.intact);
+    assert!(clean.tampered_versions.is_empty());
+    assert_eq!(clean.stored_hash, clean.computed_hash);
+
+    // Rewrite the original version behind the hash it was stored with
+    let mut versions = Vector::new();
+    for mut version in store.record_versions.get(&record_id).unwrap().unwrap().iter().unwrap() {
+        if version.version == 1 {
+            version.data = b"rewritten".to_vec();
+        }
+        versions.push(version).unwrap();
+    }
+    store.record_versions.insert(record_id.clone(), versions).unwrap();
+    let rewritten = report(&mut store);
+    assert!(rewritten.intact);
+    assert_eq!(rewritten.tampered_versions, vec![1]);
+
+    let mut record = store.records.get(&record_id).unwrap().unwrap();
+    record.data = b"tampered".to_vec();
+    store.records.insert(record_id.clone(), record).unwrap();
+    let tampered = report(&mut store);
+    assert!(!tampered.intact);
+    assert_eq!(tampered.stored_hash, content_hash(b"second"));
+    assert_eq!(tampered.computed_hash, content_hash(b"tampered"));
+    assert_eq!(tampered.chain_key.content_hash, format!("0x{}", content_hash(b"tampered")));
+}
+
+#[test]
+fn uploads_must_match_their_declared_hash() {
+    let mut store = setup();
+    act_as(ALICE);
+    let data = b"resting heart rate 62".to_vec();
+    assert_eq!(
+        code(store.store_patient_data(ALICE.into(), data.clone(), Some(content_hash(b"something else")), RecordType::Observation, None)),
+        "INVALID_INPUT"
+    );
+    assert!(store.get_patient_records(ALICE).unwrap().is_empty());
+
+    let declared = content_hash(&data).to_uppercase();
+    let record_id = store.store_patient_data(ALICE.into(), data.clone(), Some(declared), RecordType::Observation, None).unwrap();
+    assert_eq!(store.records.get(&record_id).unwrap().unwrap().content_hash, content_hash(&data));
+}
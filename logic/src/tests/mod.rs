@@ -0,0 +1,175 @@
+//! Unit tests for the context logic.
+//!
+//! Calls run against a mocked executor: `env` stands in for
+//! `calimero_sdk::env`, so each test chooses which key signs a call and what
+//! time the context reports.
+
+use crate::*;
+
+/// Asserts that each call fails with the given error code.
+macro_rules! assert_codes {
+    ($expected:literal: $($call:expr;)+) => {$(
+        assert_eq!(code($call), $expected, "{}", stringify!($call));
+    )+};
+}
+
+mod access;
+mod auth;
+mod deidentify;
+mod erasure;
+mod errors;
+mod integrity;
+mod pools;
+mod records;
+
+pub mod env {
+    use std::cell::Cell;
+
+    thread_local! {
+        static EXECUTOR: Cell<[u8; 32]> = const { Cell::new([0; 32]) };
+        static NOW: Cell<u64> = const { Cell::new(0) };
+    }
+
+    pub fn executor_id() -> [u8; 32] {
+        EXECUTOR.with(Cell::get)
+    }
+
+    pub fn context_id() -> [u8; 32] {
+        [0xc0; 32]
+    }
+
+    pub fn time_now() -> u64 {
+        NOW.with(Cell::get)
+    }
+
+    pub fn random_bytes(buf: &mut [u8]) {
+        buf.fill(0x5e);
+    }
+
+    pub fn log(_message: &str) {}
+
+    pub fn set_executor(key: [u8; 32]) {
+        EXECUTOR.with(|executor| executor.set(key));
+    }
+
+    pub fn set_time(nanos: u64) {
+        NOW.with(|now| now.set(nanos));
+    }
+}
+
+/// Context time when every test starts: 2024-01-01T00:00:00Z.
+pub const START_SECS: u64 = 1_704_067_200;
+
+pub const ADMIN: &str = "admin";
+pub const ALICE: &str = "alice";
+pub const BOB: &str = "bob";
+pub const HOSPITAL: &str = "hospital";
+pub const CLINIC: &str = "clinic";
+pub const LAB: &str = "lab";
+pub const RIVAL_LAB: &str = "rival-lab";
+pub const AUDITOR: &str = "auditor";
+pub const BROKER: &str = "broker";
+
+/// Identities registered by `setup`, with the executor key byte each signs with.
+const ACTORS: &[(&str, u8, IdentityKind, Option<Role>)] = &[
+    (ADMIN, 1, IdentityKind::Provider, None),
+    (ALICE, 2, IdentityKind::Patient, None),
+    (BOB, 3, IdentityKind::Patient, None),
+    (HOSPITAL, 4, IdentityKind::Provider, Some(Role::Provider)),
+    (CLINIC, 5, IdentityKind::Provider, Some(Role::Provider)),
+    (LAB, 6, IdentityKind::ResearchEntity, Some(Role::Researcher)),
+    (RIVAL_LAB, 7, IdentityKind::ResearchEntity, Some(Role::Researcher)),
+    (AUDITOR, 8, IdentityKind::Provider, Some(Role::Auditor)),
+    (BROKER, 9, IdentityKind::Provider, Some(Role::HonestBroker)),
+];
+
+/// Key byte of a key no identity is bound to.
+pub const STRANGER_KEY: u8 = 0xee;
+
+pub fn key(byte: u8) -> [u8; 32] {
+    [byte; 32]
+}
+
+pub fn key_of(identity_id: &str) -> [u8; 32] {
+    let (_, byte, _, _) = ACTORS
+        .iter()
+        .find(|(id, ..)| *id == identity_id)
+        .unwrap_or_else(|| panic!("unknown test identity {}", identity_id));
+    key(*byte)
+}
+
+pub fn key_string(byte: u8) -> String {
+    bs58::encode(key(byte)).into_string()
+}
+
+/// Makes `identity_id` the executor of the following calls.
+pub fn act_as(identity_id: &str) {
+    env::set_executor(key_of(identity_id));
+}
+
+pub fn advance_secs(secs: u64) {
+    env::set_time(env::time_now() + time::secs_to_nanos(secs));
+}
+
+/// Unix seconds `secs` from the current context time.
+pub fn secs_from_now(secs: u64) -> u64 {
+    env::time_now() / time::NANOS_PER_SEC + secs
+}
+
+/// A context created by `admin` with every actor registered and holding its role.
+pub fn setup() -> HealthDataStore {
+    env::set_time(time::from_unix_secs(START_SECS));
+    act_as(ADMIN);
+    let mut store = HealthDataStore::init();
+
+    for (identity_id, _, kind, _) in ACTORS {
+        act_as(identity_id);
+        store.register_identity(identity_id.to_string(), *kind, identity_id.to_string()).unwrap();
+    }
+
+    act_as(ADMIN);
+    for (identity_id, _, _, role) in ACTORS {
+        if let Some(role) = role {
+            store.assign_role(identity_id.to_string(), *role).unwrap();
+        }
+    }
+    store
+}
+
+/// Stores a plaintext JSON record for `patient_id`, acting as them.
+pub fn add_record(store: &mut HealthDataStore, patient_id: &str) -> String {
+    act_as(patient_id);
+    let data = br#"{"resourceType":"Observation","note":"resting heart rate 62"}"#.to_vec();
+    let record_id = store
+        .store_patient_data(patient_id.to_string(), data, None, RecordType::Observation, None)
+        .unwrap();
+    advance_secs(1);
+    record_id
+}
+
+/// Creates an active, funded pool owned by `entity_id` that pays 10 per participant.
+pub fn add_pool(store: &mut HealthDataStore, entity_id: &str) -> String {
+    act_as(entity_id);
+    let pool_id = store
+        .create_research_pool(
+            entity_id.to_string(),
+            "Cardiology".to_string(),
+            "Resting heart rate study".to_string(),
+            10,
+            secs_from_now(30 * time::SECS_PER_DAY),
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+    store.fund_pool(&pool_id, 100, None).unwrap();
+    pool_id
+}
+
+/// Stable error code of a failed call, or "OK".
+pub fn code<T>(result: Result<T, HealthError>) -> &'static str {
+    match result {
+        Ok(_) => "OK",
+        Err(err) => err.code(),
+    }
+}
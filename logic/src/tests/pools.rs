@@ -0,0 +1,243 @@
+//! Research pools: budgets, updates and the submissions patients make to them.
+
+use super::*;
+
+/// Submits `record_id` to `pool_id` as Alice and accepts it as the pool owner.
+fn accept_submission(store: &mut HealthDataStore, pool_id: &str, record_id: &str) {
+    act_as(ALICE);
+    store.submit_to_pool(pool_id, ALICE, vec![record_id.to_string()]).unwrap();
+    let participant_id = store.get_participant_id(pool_id, ALICE).unwrap();
+    let owner = store.get_research_pool(pool_id).unwrap().unwrap().entity_id;
+    act_as(&owner);
+    store.update_submission_status(pool_id, &participant_id, SubmissionStatus::Accepted, None).unwrap();
+}
+
+#[test]
+fn deposits_fund_a_single_pool_once() {
+    let mut store = setup();
+    let pool_id = add_pool(&mut store, LAB);
+    let other_pool = add_pool(&mut store, LAB);
+
+    act_as(LAB);
+    let ledger = store.fund_pool(&pool_id, 50, Some(" 0xabc ".into())).unwrap();
+    assert_eq!(ledger.funded, 150);
+    assert_eq!(ledger.deposit_refs, vec!["0xabc".to_string()]);
+
+    assert_codes!("ALREADY_EXISTS":
+        store.fund_pool(&pool_id, 50, Some("0xabc".into()));
+        store.fund_pool(&other_pool, 50, Some("0xabc".into()));
+    );
+    assert_eq!(code(store.fund_pool(&pool_id, 50, Some(" ".into()))), "INVALID_INPUT");
+    assert_eq!(store.get_pool_ledger(&pool_id).unwrap().funded, 150);
+    assert_eq!(store.get_pool_ledger(&other_pool).unwrap().funded, 100);
+}
+
+#[test]
+fn rejected_pool_updates_leave_access_alone() {
+    let mut store = setup();
+    let record_id = add_record(&mut store, ALICE);
+    let pool_id = add_pool(&mut store, LAB);
+    accept_submission(&mut store, &pool_id, &record_id);
+
+    let invalid_criteria = EligibilityCriteria { max_record_age_secs: Some(0), ..Default::default() };
+    assert_codes!("INVALID_INPUT":
+        store.update_research_pool(&pool_id, None, None, None, Some(PoolStatus::Closed), Some(0), None);
+        store.update_research_pool(&pool_id, None, None, None, Some(PoolStatus::Closed), None, Some(invalid_criteria));
+    );
+
+    let record = store.records.get(&record_id).unwrap().unwrap();
+    assert!(record.has_active_grant(LAB, env::time_now()));
+    assert_eq!(store.get_research_pool(&pool_id).unwrap().unwrap().status, PoolStatus::Active);
+
+    store.update_research_pool(&pool_id, None, None, None, Some(PoolStatus::Closed), None, None).unwrap();
+    let record = store.records.get(&record_id).unwrap().unwrap();
+    assert!(!record.has_active_grant(LAB, env::time_now()));
+}
+
+#[test]
+fn pool_grants_sit_beside_direct_and_other_pool_grants() {
+    let mut store = setup();
+    let record_id = add_record(&mut store, ALICE);
+    let first_pool = add_pool(&mut store, LAB);
+    let second_pool = add_pool(&mut store, LAB);
+
+    act_as(ALICE);
+    store.grant_access(ALICE.into(), LAB.into(), secs_from_now(time::SECS_PER_DAY), None).unwrap();
+    accept_submission(&mut store, &first_pool, &record_id);
+    accept_submission(&mut store, &second_pool, &record_id);
+
+    let pools_of = |store: &HealthDataStore| -> Vec<Option<String>> {
+        act_as(ALICE);
+        let mut pools: Vec<Option<String>> = store
+            .list_access_grants(ALICE, false)
+            .unwrap()
+            .into_iter()
+            .map(|view| view.grant.pool_id)
+            .collect();
+        pools.sort();
+        pools
+    };
+    let mut expected = vec![None, Some(first_pool.clone()), Some(second_pool.clone())];
+    expected.sort();
+    assert_eq!(pools_of(&store), expected);
+
+    // Closing one pool leaves the direct grant and the other pool's grant
+    act_as(LAB);
+    store.update_research_pool(&first_pool, None, None, None, Some(PoolStatus::Closed), None, None).unwrap();
+    assert_eq!(pools_of(&store), vec![None, Some(second_pool.clone())]);
+
+    act_as(ALICE);
+    store.withdraw_submission(&second_pool, ALICE, None).unwrap();
+    assert_eq!(pools_of(&store), vec![None]);
+    let record = store.records.get(&record_id).unwrap().unwrap();
+    assert!(record.has_active_grant(LAB, env::time_now()));
+}
+
+#[test]
+fn resubmitting_keeps_the_earlier_history() {
+    let mut store = setup();
+    let record_id = add_record(&mut store, ALICE);
+    let pool_id = add_pool(&mut store, LAB);
+
+    act_as(ALICE);
+    store.submit_to_pool(&pool_id, ALICE, vec![record_id.clone()]).unwrap();
+    assert_eq!(code(store.submit_to_pool(&pool_id, ALICE, vec![record_id.clone()])), "ALREADY_EXISTS");
+    store.withdraw_submission(&pool_id, ALICE, Some("changed my mind".into())).unwrap();
+    store.submit_to_pool(&pool_id, ALICE, vec![record_id]).unwrap();
+
+    let submissions = store.get_patient_submissions(ALICE).unwrap();
+    let history: Vec<SubmissionStatus> = submissions[0].submission.history.iter().map(|change| change.status).collect();
+    assert_eq!(history, vec![SubmissionStatus::Pending, SubmissionStatus::Withdrawn, SubmissionStatus::Pending]);
+    assert_eq!(submissions[0].submission.status, SubmissionStatus::Pending);
+}
+
+fn anonymized_only<T>(result: Result<AccessResult<T>, HealthError>) -> bool {
+    matches!(result, Ok(AccessResult::Denied(DenialReason::AnonymizedOnly)))
+}
+
+#[test]
+fn pool_grants_only_serve_the_anonymized_derivative() {
+    let mut store = setup();
+    let record_id = add_record(&mut store, ALICE);
+    let pool_id = add_pool(&mut store, LAB);
+    accept_submission(&mut store, &pool_id, &record_id);
+
+    act_as(LAB);
+    assert!(anonymized_only(store.get_record(&record_id, LAB)));
+    assert!(anonymized_only(store.get_patient_data(ALICE, LAB)));
+    assert!(anonymized_only(store.get_record_version(&record_id, LAB, 1)));
+    let research = format!("research:{}", pool_id);
+    assert!(anonymized_only(store.access_patient_data(ALICE, LAB, &research)));
+    assert!(store.list_patient_records(ALICE, LAB, None, None, None, None, None, None).unwrap().items.is_empty());
+    assert!(store.list_authorized_reports(LAB).unwrap().is_empty());
+    assert_eq!(store.export_fhir_bundle(ALICE, LAB).unwrap()["entry"], serde_json::json!([]));
+
+    // The derivative is what the pool owner gets
+    act_as(ALICE);
+    let participant_id = store.get_participant_id(&pool_id, ALICE).unwrap();
+    act_as(LAB);
+    assert!(matches!(
+        store.get_pool_anonymized_data(&pool_id, &participant_id),
+        Ok(AccessResult::Denied(DenialReason::NotAnonymized))
+    ));
+    act_as(ALICE);
+    store.anonymize_record(&record_id).unwrap();
+    act_as(LAB);
+    let Ok(AccessResult::Granted(derivatives)) = store.get_pool_anonymized_data(&pool_id, &participant_id) else {
+        panic!("the pool owner cannot read the derivative");
+    };
+    assert_eq!(derivatives.len(), 1);
+    assert_eq!(derivatives[0].subject_pseudonym, participant_id);
+}
+
+/// The derivative of Alice's record as the owner of `pool_id` reads it.
+fn pool_derivative(store: &mut HealthDataStore, pool_id: &str) -> AnonymizedRecord {
+    act_as(ALICE);
+    let participant_id = store.get_participant_id(pool_id, ALICE).unwrap();
+    let owner = store.get_research_pool(pool_id).unwrap().unwrap().entity_id;
+    act_as(&owner);
+    match store.get_pool_anonymized_data(pool_id, &participant_id).unwrap() {
+        AccessResult::Granted(mut derivatives) => derivatives.remove(0),
+        AccessResult::Denied(reason) => panic!("{} cannot read the derivative: {:?}", owner, reason),
+    }
+}
+
+#[test]
+fn pool_derivatives_cannot_be_linked_across_pools() {
+    let mut store = setup();
+    act_as(ALICE);
+    let data = br#"{"patientId":"alice","effectiveDateTime":"2024-03-01","value":62}"#.to_vec();
+    let record_id = store.store_patient_data(ALICE.into(), data, None, RecordType::Observation, None).unwrap();
+    let lab_pool = add_pool(&mut store, LAB);
+    let rival_pool = add_pool(&mut store, RIVAL_LAB);
+    accept_submission(&mut store, &lab_pool, &record_id);
+    accept_submission(&mut store, &rival_pool, &record_id);
+    act_as(ALICE);
+    let stored = store.anonymize_record(&record_id).unwrap();
+
+    let lab = pool_derivative(&mut store, &lab_pool);
+    let rival = pool_derivative(&mut store, &rival_pool);
+    assert_ne!(lab.subject_pseudonym, rival.subject_pseudonym);
+    assert_ne!(lab.anon_id, rival.anon_id);
+    for derivative in [&lab, &rival] {
+        let text = String::from_utf8(derivative.data.clone()).unwrap();
+        assert!(text.contains(&derivative.subject_pseudonym), "{text}");
+        for linkable in [ALICE, "2024-03-01", stored.subject_pseudonym.as_str(), stored.anon_id.as_str()] {
+            assert!(!text.contains(linkable) && derivative.anon_id != linkable, "{text}");
+        }
+    }
+
+    // Participant IDs only resolve in their own pool
+    act_as(RIVAL_LAB);
+    assert_eq!(code(store.get_pool_anonymized_data(&rival_pool, &lab.subject_pseudonym)), "NOT_FOUND");
+}
+
+#[test]
+fn statuses_decode_only_from_their_own_names() {
+    use calimero_sdk::borsh::{from_slice, to_vec};
+
+    let stored = to_vec(&PoolStatus::Cancelled).unwrap();
+    assert_eq!(from_slice::<PoolStatus>(&stored).unwrap(), PoolStatus::Cancelled);
+    let stored = to_vec(&SubmissionStatus::Rewarded).unwrap();
+    assert_eq!(from_slice::<SubmissionStatus>(&stored).unwrap(), SubmissionStatus::Rewarded);
+
+    for name in ["open", "Active", "garbage"] {
+        assert!(from_slice::<PoolStatus>(&to_vec(name).unwrap()).is_err(), "{name}");
+    }
+    for name in ["approved", "paid", ""] {
+        assert!(from_slice::<SubmissionStatus>(&to_vec(name).unwrap()).is_err(), "{name}");
+    }
+    assert!(serde_json::from_str::<SubmissionStatus>("\"approved\"").is_err());
+}
+
+#[test]
+fn ended_pools_stop_reviewing_submissions() {
+    let mut store = setup();
+    let record_id = add_record(&mut store, ALICE);
+    let closing = add_pool(&mut store, LAB);
+    let expiring = add_pool(&mut store, LAB);
+    act_as(ALICE);
+    store.submit_to_pool(&closing, ALICE, vec![record_id.clone()]).unwrap();
+    store.submit_to_pool(&expiring, ALICE, vec![record_id]).unwrap();
+    let closing_participant = store.get_participant_id(&closing, ALICE).unwrap();
+    let expiring_participant = store.get_participant_id(&expiring, ALICE).unwrap();
+
+    // Closing rejects what is still pending, with the reason in its history
+    act_as(LAB);
+    store.update_research_pool(&closing, None, None, None, Some(PoolStatus::Closed), None, None).unwrap();
+    let submission = store.get_pool_submissions(&closing, None, None, None).unwrap().items.remove(0);
+    assert_eq!(submission.status, SubmissionStatus::Rejected);
+    let change = submission.history.last().unwrap();
+    assert_eq!((change.changed_by.as_str(), change.reason.as_deref()), (LAB, Some("pool closed")));
+    assert_eq!(
+        code(store.update_submission_status(&closing, &closing_participant, SubmissionStatus::Accepted, None)),
+        "INVALID_STATE"
+    );
+
+    advance_secs(31 * time::SECS_PER_DAY);
+    assert_eq!(
+        code(store.update_submission_status(&expiring, &expiring_participant, SubmissionStatus::Accepted, None)),
+        "EXPIRED"
+    );
+    assert_eq!(store.pool_ledger(&expiring).unwrap().reserved, 0);
+}
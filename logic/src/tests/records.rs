@@ -0,0 +1,301 @@
+//! Storing records and reading them back: imports, filters and the refusal
+//! reasons callers get.
+
+use super::*;
+
+#[test]
+fn list_patient_records_filters_by_unix_seconds() {
+    let mut store = setup();
+    let first_secs = secs_from_now(0);
+    let first = add_record(&mut store, ALICE);
+    advance_secs(time::SECS_PER_DAY);
+    let second_secs = secs_from_now(0);
+    let second = add_record(&mut store, ALICE);
+
+    act_as(ALICE);
+    let ids = |page: Page<PatientDataResponse>| -> Vec<String> {
+        page.items.into_iter().map(|item| item.record_id).collect()
+    };
+
+    let page = store.list_patient_records(ALICE, ALICE, None, None, Some(second_secs), None, None, None).unwrap();
+    assert_eq!(ids(page), vec![second.clone()]);
+
+    // `to` covers the whole second the first record was written in
+    let page = store.list_patient_records(ALICE, ALICE, None, None, None, Some(first_secs), None, None).unwrap();
+    assert_eq!(ids(page), vec![first.clone()]);
+
+    let page = store.list_patient_records(ALICE, ALICE, None, None, Some(first_secs), Some(second_secs), None, None).unwrap();
+    assert_eq!(ids(page), vec![second, first]);
+}
+
+#[test]
+fn access_patient_data_reports_missing_and_expired_consent() {
+    let mut store = setup();
+    add_record(&mut store, ALICE);
+    act_as(ALICE);
+    store.add_consent(ALICE.into(), HOSPITAL.into(), "treatment".into(), time::SECS_PER_DAY, String::new()).unwrap();
+
+    act_as(HOSPITAL);
+    assert!(matches!(store.access_patient_data(ALICE, HOSPITAL, " Treatment "), Ok(AccessResult::Granted(_))));
+    assert!(matches!(
+        store.access_patient_data(ALICE, HOSPITAL, "billing"),
+        Ok(AccessResult::Denied(DenialReason::ConsentMissing))
+    ));
+
+    advance_secs(time::SECS_PER_DAY + 1);
+    assert!(matches!(
+        store.access_patient_data(ALICE, HOSPITAL, "treatment"),
+        Ok(AccessResult::Denied(DenialReason::ConsentExpired))
+    ));
+}
+
+#[test]
+fn import_fhir_keeps_quantities_without_a_unit_in_the_resource_only() {
+    let mut store = setup();
+    let observation = serde_json::json!({
+        "resourceType": "Observation",
+        "status": "final",
+        "code": { "coding": [{ "system": "http://loinc.org", "code": "9279-1" }] },
+        "valueQuantity": { "value": 16 }
+    });
+
+    act_as(ALICE);
+    let record_ids = store.import_fhir(ALICE.into(), observation).unwrap();
+    let record = store.records.get(&record_ids[0]).unwrap().unwrap();
+    assert!(matches!(&record.structured[..], [ClinicalEntry::Observation { value: None, .. }]));
+    let stored: serde_json::Value = serde_json::from_slice(&record.data).unwrap();
+    assert_eq!(stored["valueQuantity"]["value"], 16);
+}
+
+#[test]
+fn anonymizing_imported_fhir_leaves_no_identifiers() {
+    let mut store = setup();
+    let narrative = serde_json::json!({ "status": "generated", "div": "<div>Alice Smith, MRN 884201</div>" });
+    let bundle = serde_json::json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "entry": [
+            { "resource": {
+                "resourceType": "Patient",
+                "id": "pat-884201",
+                "text": narrative,
+                "identifier": [{ "system": "urn:mrn", "value": "884201" }],
+                "name": [{ "family": "Smith", "given": ["Alice"] }],
+                "telecom": [{ "system": "phone", "value": "555-0100" }],
+                "gender": "female",
+                "birthDate": "1984-06-02",
+                "generalPractitioner": [{ "reference": "Practitioner/dr-jones", "display": "Dr. Jones" }]
+            } },
+            { "resource": {
+                "resourceType": "Observation",
+                "id": "obs-17",
+                "text": narrative,
+                "status": "final",
+                "code": { "coding": [{ "system": "http://loinc.org", "code": "8867-4", "display": "Heart rate" }] },
+                "subject": { "reference": "Patient/pat-884201", "display": "Alice Smith" },
+                "performer": [{ "reference": "Practitioner/dr-jones", "display": "Dr. Jones" }],
+                "effectiveDateTime": "2024-03-01T08:30:00Z",
+                "valueQuantity": { "value": 62, "unit": "beats/minute" },
+                "note": [{ "authorString": "Dr. Jones", "text": "resting" }]
+            } }
+        ]
+    });
+
+    act_as(ALICE);
+    for record_id in store.import_fhir(ALICE.into(), bundle).unwrap() {
+        let derivative = store.anonymize_record(&record_id).unwrap();
+        let text = String::from_utf8(derivative.data).unwrap();
+        for identifier in ["884201", "Alice", "Smith", "555-0100", "1984-06-02", "dr-jones", "Jones", "obs-17", "2024-03-01"] {
+            assert!(!text.contains(identifier), "{identifier} left in {text}");
+        }
+        if text.contains("Observation") {
+            let resource: serde_json::Value = serde_json::from_str(&text).unwrap();
+            let expected = format!("Patient/{}", derivative.subject_pseudonym);
+            assert_eq!(resource["subject"], serde_json::json!({ "reference": expected }));
+            assert_eq!(resource["code"]["coding"][0]["display"], "Heart rate");
+        }
+    }
+}
+
+#[test]
+fn import_patient_bundle_rejects_tampered_versions_before_storing() {
+    let mut store = setup();
+    let record_id = add_record(&mut store, ALICE);
+    act_as(ALICE);
+    store.update_record(&record_id, b"revised".to_vec(), RecordType::Document, None, None).unwrap();
+    let bundle = store.export_patient_bundle(ALICE).unwrap();
+
+    let resealed = |mut bundle: PatientBundle| {
+        bundle.checksum = bundle.compute_checksum().unwrap();
+        bundle
+    };
+    let mut tampered = bundle.clone();
+    tampered.record_versions[0].data = b"forged".to_vec();
+    let mut orphaned = bundle.clone();
+    orphaned.record_versions[0].record_id = "rec-elsewhere".into();
+    let mut duplicated = bundle.clone();
+    duplicated.record_versions.push(duplicated.record_versions[0].clone());
+
+    act_as(BOB);
+    assert_codes!("INVALID_INPUT":
+        store.import_patient_bundle(BOB.into(), resealed(tampered));
+        store.import_patient_bundle(BOB.into(), resealed(orphaned));
+        store.import_patient_bundle(BOB.into(), resealed(duplicated));
+    );
+    assert!(store.get_patient_records(BOB).unwrap().is_empty());
+
+    let summary = store.import_patient_bundle(BOB.into(), bundle).unwrap();
+    assert_eq!(summary.record_versions, 2);
+}
+
+#[test]
+fn imported_versions_are_attributed_to_the_importer() {
+    let mut store = setup();
+    let record_id = add_record(&mut store, ALICE);
+    act_as(ALICE);
+    store.update_record(&record_id, b"revised".to_vec(), RecordType::Document, None, None).unwrap();
+
+    // A recomputed checksum lets a patient name anyone as the author
+    let mut bundle = store.export_patient_bundle(ALICE).unwrap();
+    bundle.record_versions[1].author_id = HOSPITAL.into();
+    bundle.checksum = bundle.compute_checksum().unwrap();
+    let source_context = bundle.source_context.clone();
+
+    act_as(BOB);
+    let summary = store.import_patient_bundle(BOB.into(), bundle).unwrap();
+    let AccessResult::Granted(history) = store.get_record_history(&summary.record_ids[0], BOB).unwrap() else {
+        panic!("importer cannot read the imported history");
+    };
+    assert!(history.iter().all(|version| version.author_id == BOB));
+    let claimed: Vec<&str> = history
+        .iter()
+        .map(|version| version.imported_from.as_ref().unwrap())
+        .inspect(|imported| assert_eq!(imported.source_context, source_context))
+        .map(|imported| imported.claimed_author_id.as_str())
+        .collect();
+    assert_eq!(claimed, vec![HOSPITAL, ALICE]);
+}
+
+#[test]
+fn history_keeps_every_version_newest_first() {
+    let mut store = setup();
+    let record_id = add_record(&mut store, ALICE);
+    act_as(ALICE);
+    for (data, reason) in [("second", "corrected rate"), ("third", "  ")] {
+        advance_secs(1);
+        store.update_record(&record_id, data.into(), RecordType::Observation, None, Some(reason.into())).unwrap();
+    }
+
+    let AccessResult::Granted(history) = store.get_record_history(&record_id, ALICE).unwrap() else {
+        panic!("the owner cannot read the history");
+    };
+    let summary: Vec<(u32, Option<&str>)> = history.iter().map(|v| (v.version, v.reason.as_deref())).collect();
+    assert_eq!(summary, vec![(3, None), (2, Some("corrected rate")), (1, None)]);
+    assert!(history.iter().all(|v| v.author_id == ALICE));
+    assert!(history.windows(2).all(|pair| pair[0].created_at > pair[1].created_at));
+
+    // Each version keeps its own payload and hash
+    let AccessResult::Granted(second) = store.get_record_version(&record_id, ALICE, 2).unwrap() else {
+        panic!("the owner cannot read version 2");
+    };
+    assert_eq!(second.data, b"second");
+    assert_eq!(second.content_hash, content_hash(b"second"));
+    assert!(matches!(store.get_record_version(&record_id, ALICE, 4).unwrap(), AccessResult::Denied(DenialReason::NotFound)));
+
+    act_as(HOSPITAL);
+    assert!(matches!(
+        store.get_record_version(&record_id, HOSPITAL, 1).unwrap(),
+        AccessResult::Denied(DenialReason::NotAuthorized)
+    ));
+    act_as(ALICE);
+    store.grant_access(ALICE.into(), HOSPITAL.into(), secs_from_now(time::SECS_PER_DAY), None).unwrap();
+    act_as(HOSPITAL);
+    let AccessResult::Granted(first) = store.get_record_version(&record_id, HOSPITAL, 1).unwrap() else {
+        panic!("a granted provider cannot read version 1");
+    };
+    assert_eq!(first.data, br#"{"resourceType":"Observation","note":"resting heart rate 62"}"#);
+}
+
+#[test]
+fn deleted_records_restore_until_their_purge_date() {
+    let mut store = setup();
+    let kept = add_record(&mut store, ALICE);
+    let lapsed = add_record(&mut store, ALICE);
+
+    act_as(ALICE);
+    assert_eq!(code(store.restore_record(&kept)), "NOT_FOUND");
+    store.delete_record(&kept).unwrap();
+    store.delete_record(&lapsed).unwrap();
+    assert_eq!(store.list_deleted_records(ALICE).unwrap().len(), 2);
+    assert_eq!(code(store.restore_record(&kept)), "OK");
+    assert_eq!(store.get_patient_records(ALICE).unwrap().len(), 1);
+
+    advance_secs(DEFAULT_RETENTION_SECS);
+    assert_eq!(code(store.restore_record(&lapsed)), "EXPIRED");
+    assert_eq!(store.list_deleted_records(ALICE).unwrap().len(), 1);
+}
+
+#[test]
+fn purging_skips_patients_under_legal_hold() {
+    let mut store = setup();
+    let alices = add_record(&mut store, ALICE);
+    let bobs = add_record(&mut store, BOB);
+    act_as(ALICE);
+    store.delete_record(&alices).unwrap();
+    act_as(BOB);
+    store.delete_record(&bobs).unwrap();
+
+    act_as(ADMIN);
+    assert_eq!(store.purge_deleted_records(None).unwrap(), 0);
+    store.place_legal_hold(ALICE.into(), "litigation".into()).unwrap();
+    advance_secs(DEFAULT_RETENTION_SECS);
+    assert_eq!(store.purge_deleted_records(None).unwrap(), 1);
+    assert!(store.records.get(&bobs).unwrap().is_none());
+
+    act_as(ALICE);
+    let held = store.list_deleted_records(ALICE).unwrap();
+    assert_eq!(held.len(), 1);
+    assert!(held[0].on_legal_hold);
+
+    act_as(ADMIN);
+    store.release_legal_hold(ALICE).unwrap();
+    assert_eq!(store.purge_deleted_records(None).unwrap(), 1);
+    assert!(store.records.get(&alices).unwrap().is_none());
+}
+
+#[test]
+fn structured_entries_are_checked_against_the_record_type() {
+    let mut store = setup();
+    let entry = |value: serde_json::Value| -> ClinicalEntry { serde_json::from_value(value).unwrap() };
+    let loinc = serde_json::json!({ "system": "http://loinc.org", "code": "8867-4" });
+    let heart_rate = |value: serde_json::Value, effective: &str| entry(serde_json::json!({
+        "kind": "observation", "code": loinc, "value": value, "effective": effective
+    }));
+    let condition = |onset: &str, abatement: &str| entry(serde_json::json!({
+        "kind": "condition",
+        "code": { "system": "http://snomed.info/sct", "code": "38341003" },
+        "onset": onset,
+        "abatement": abatement
+    }));
+    let rate = |unit: &str| serde_json::json!({ "type": "quantity", "value": { "value": 62.0, "unit": unit } });
+
+    act_as(ALICE);
+    let mut store_entries = |record_type: RecordType, entries: Vec<ClinicalEntry>| {
+        store.store_patient_data(ALICE.into(), b"payload".to_vec(), None, record_type, Some(entries))
+    };
+    assert_codes!("INVALID_INPUT":
+        store_entries(RecordType::Condition, vec![heart_rate(rate("/min"), "2024-03-01")]);
+        store_entries(RecordType::Observation, vec![heart_rate(rate(" "), "2024-03-01")]);
+        store_entries(RecordType::Observation, vec![heart_rate(rate("/min"), "01/03/2024")]);
+        store_entries(RecordType::Observation, vec![heart_rate(rate("/min"), "2024-13-01")]);
+        store_entries(RecordType::Condition, vec![condition("2024-03-01", "2023-12-31")]);
+        store_entries(RecordType::Custom(String::new()), vec![]);
+    );
+
+    assert_codes!("OK":
+        store_entries(RecordType::Observation, vec![heart_rate(rate("/min"), "2024-03-01T08:30:00Z")]);
+        store_entries(RecordType::Condition, vec![condition("2023-12-31", "2024-03-01")]);
+        // Types without an entry kind of their own may mix entries
+        store_entries(RecordType::Document, vec![heart_rate(rate("/min"), "2024-03-01"), condition("2024-01-01", "2024-02-01")]);
+    );
+}
@@ -0,0 +1,69 @@
+//! Time helpers shared by consents, access grants, research pools and exports.
+//!
+//! Every timestamp stored in the context is in nanoseconds since the Unix
+//! epoch, matching `env::time_now()`. Callers pass durations and absolute
+//! expiries in seconds, which are converted here.
+
+#[cfg(not(test))]
+use calimero_sdk::env;
+#[cfg(test)]
+use crate::tests::env;
+
+pub const NANOS_PER_SEC: u64 = 1_000_000_000;
+pub const SECS_PER_HOUR: u64 = 3_600;
+pub const SECS_PER_DAY: u64 = 86_400;
+
+/// Current context time in nanoseconds.
+pub fn now() -> u64 {
+    env::time_now()
+}
+
+pub fn secs_to_nanos(secs: u64) -> u64 {
+    secs.saturating_mul(NANOS_PER_SEC)
+}
+
+/// Nanosecond timestamp `duration_secs` from now.
+pub fn expiry_after(duration_secs: u64) -> u64 {
+    now().saturating_add(secs_to_nanos(duration_secs))
+}
+
+/// Converts a Unix timestamp in seconds, as sent by the frontend, to nanoseconds.
+pub fn from_unix_secs(timestamp_secs: u64) -> u64 {
+    secs_to_nanos(timestamp_secs)
+}
+
+/// Nanosecond timestamp as an RFC 3339 UTC instant, e.g. `2024-05-01T09:30:00Z`.
+pub fn to_rfc3339(timestamp: u64) -> String {
+    let secs = timestamp / NANOS_PER_SEC;
+    let (year, month, day) = civil_from_days((secs / SECS_PER_DAY) as i64);
+    let secs_of_day = secs % SECS_PER_DAY;
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year, month, day,
+        secs_of_day / SECS_PER_HOUR, secs_of_day % SECS_PER_HOUR / 60, secs_of_day % 60
+    )
+}
+
+// Proleptic Gregorian conversions, after Howard Hinnant's `chrono`-compatible algorithms.
+pub fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let mp = (month as i64 + 9) % 12;
+    let doy = (153 * mp + 2) / 5 + day as i64 - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+pub fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}